@@ -5,6 +5,7 @@ use std::{
         Arc,
     },
     thread,
+    time::{Duration, Instant},
 };
 
 #[cfg(feature = "bench")]
@@ -21,35 +22,14 @@ fn lock_contention_yield(threads: usize) {
         let done = done.clone();
         let lock = lock.clone();
         thread::spawn(move || {
-            let val = loop {
-                if let Ok(val) = lock.try_take() {
-                    break val;
-                }
-                loop {
-                    thread::yield_now();
-                    if lock.is_some_unlocked() {
-                        break;
-                    }
-                }
-            };
+            let val = lock.take_yield();
             done.fetch_add(val, Ordering::AcqRel);
         });
     }
     let mut expected = 0;
     for val in 0..threads - 1 {
         expected += val;
-        loop {
-            if let Ok(mut guard) = lock.try_lock_none() {
-                guard.replace(val);
-                break;
-            }
-            loop {
-                thread::yield_now();
-                if !lock.is_locked() {
-                    break;
-                }
-            }
-        }
+        lock.fill_yield(val);
     }
     loop {
         if done.load(Ordering::Relaxed) == expected {
@@ -74,14 +54,122 @@ fn lock_contention_spin(threads: usize) {
     let mut expected = 0;
     for val in 0..threads - 1 {
         expected += val;
-        let mut guard = lock.spin_lock_none();
-        guard.replace(val);
+        lock.spin_fill(val);
+    }
+    while done.load(Ordering::Relaxed) != expected {
+        spin_loop();
+    }
+}
+
+// compare strong and weak lock acquisition within a spin loop
+fn lock_contention_spin_lock(threads: usize, weak: bool) {
+    let lock = Arc::new(OptionLock::new(0));
+    let handles = (0..threads)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let mut guard = loop {
+                        let result = if weak {
+                            lock.try_lock_weak()
+                        } else {
+                            lock.try_lock()
+                        };
+                        if let Ok(guard) = result {
+                            break guard;
+                        }
+                        spin_loop();
+                    };
+                    *guard.as_mut().unwrap() += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(lock.try_take(), Ok(threads * 100));
+}
+
+// compare with lock_contention_spin, using randomized backoff between attempts
+#[cfg(feature = "jitter")]
+fn lock_contention_jitter(threads: usize) {
+    let lock = Arc::new(OptionLock::empty());
+    let done = Arc::new(AtomicUsize::new(0));
+    for _ in 0..threads - 1 {
+        let done = done.clone();
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let val = loop {
+                if let Ok(mut guard) = lock.try_lock_backoff(16) {
+                    if let Some(val) = guard.take() {
+                        break val;
+                    }
+                }
+            };
+            done.fetch_add(val, Ordering::AcqRel);
+        });
+    }
+    let mut expected = 0;
+    for val in 0..threads - 1 {
+        expected += val;
+        loop {
+            if let Ok(mut guard) = lock.try_lock_backoff(16) {
+                if guard.is_none() {
+                    guard.replace(val);
+                    break;
+                }
+            }
+        }
     }
     while done.load(Ordering::Relaxed) != expected {
         spin_loop();
     }
 }
 
+// measure the 99th percentile latency of acquiring a contended lock, to
+// compare the tail of plain spinning against randomized backoff
+fn lock_acquire_tail_latency(threads: usize, jitter: bool) -> Duration {
+    let lock = Arc::new(OptionLock::new(0));
+    let handles = (0..threads)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                let mut waits = Vec::with_capacity(100);
+                for _ in 0..100 {
+                    let start = Instant::now();
+                    let mut guard = loop {
+                        #[cfg(feature = "jitter")]
+                        let result = if jitter {
+                            lock.try_lock_backoff(16)
+                        } else {
+                            lock.try_lock()
+                        };
+                        #[cfg(not(feature = "jitter"))]
+                        let result = {
+                            let _ = jitter;
+                            lock.try_lock()
+                        };
+                        if let Ok(guard) = result {
+                            break guard;
+                        }
+                        spin_loop();
+                    };
+                    waits.push(start.elapsed());
+                    *guard.as_mut().unwrap() += 1;
+                }
+                waits
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut waits = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    waits.sort_unstable();
+    waits[waits.len() * 99 / 100]
+}
+
 fn bench_contention(c: &mut Criterion) {
     let yield_thread_count = 500;
     c.bench_with_input(
@@ -100,6 +188,48 @@ fn bench_contention(c: &mut Criterion) {
             b.iter(|| lock_contention_spin(s));
         },
     );
+
+    for (name, weak) in [
+        ("lock_contention_strong", false),
+        ("lock_contention_weak", true),
+    ] {
+        c.bench_with_input(
+            BenchmarkId::new(name, spin_thread_count),
+            &spin_thread_count,
+            |b, &s| {
+                b.iter(|| lock_contention_spin_lock(s, weak));
+            },
+        );
+    }
+
+    #[cfg(feature = "jitter")]
+    c.bench_with_input(
+        BenchmarkId::new("lock_contention_jitter", spin_thread_count),
+        &spin_thread_count,
+        |b, &s| {
+            b.iter(|| lock_contention_jitter(s));
+        },
+    );
+
+    // the reported time is the p99 acquisition latency of each run
+    let mut tail = c.benchmark_group("lock_tail_latency");
+    for (name, jitter) in [("spin", false), ("jitter", true)] {
+        if jitter && cfg!(not(feature = "jitter")) {
+            continue;
+        }
+        tail.bench_with_input(
+            BenchmarkId::new(name, spin_thread_count),
+            &spin_thread_count,
+            |b, &s| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| lock_acquire_tail_latency(s, jitter))
+                        .sum()
+                });
+            },
+        );
+    }
+    tail.finish();
 }
 
 #[cfg(feature = "bench")]
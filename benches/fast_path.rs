@@ -0,0 +1,112 @@
+#[cfg(feature = "bench")]
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use option_lock::{Lazy, OnceCell, OptionLock};
+
+// these tests measure the latency of the try-lock operations on a single thread
+
+fn bench_try_lock(c: &mut Criterion) {
+    let lock = OptionLock::new(1u32);
+    c.bench_function("try_lock", |b| {
+        b.iter(|| black_box(&lock).try_lock().is_ok());
+    });
+    let guard = lock.try_lock().unwrap();
+    c.bench_function("try_lock_unavailable", |b| {
+        b.iter(|| black_box(&lock).try_lock().is_ok());
+    });
+    drop(guard);
+}
+
+fn bench_try_get(c: &mut Criterion) {
+    let lock = OptionLock::new(1u32);
+    c.bench_function("try_get", |b| {
+        b.iter(|| black_box(&lock).try_get().is_ok());
+    });
+    let empty = OptionLock::<u32>::empty();
+    c.bench_function("try_get_fill_state", |b| {
+        b.iter(|| black_box(&empty).try_get().is_ok());
+    });
+}
+
+fn bench_try_lock_none(c: &mut Criterion) {
+    let lock = OptionLock::<u32>::empty();
+    c.bench_function("try_lock_none", |b| {
+        b.iter(|| black_box(&lock).try_lock_none().is_ok());
+    });
+    let full = OptionLock::new(1u32);
+    c.bench_function("try_lock_none_fill_state", |b| {
+        b.iter(|| black_box(&full).try_lock_none().is_ok());
+    });
+}
+
+fn bench_try_take(c: &mut Criterion) {
+    let lock = OptionLock::<u32>::empty();
+    c.bench_function("try_fill_try_take", |b| {
+        b.iter(|| {
+            let lock = black_box(&lock);
+            lock.try_fill(1).ok();
+            lock.try_take().ok()
+        });
+    });
+}
+
+// a single exchange compared with separate take and fill operations
+fn bench_try_exchange(c: &mut Criterion) {
+    let lock = OptionLock::new(1u32);
+    c.bench_function("try_exchange", |b| {
+        b.iter(|| black_box(&lock).try_exchange(1).ok());
+    });
+    c.bench_function("try_take_try_fill", |b| {
+        b.iter(|| {
+            let lock = black_box(&lock);
+            let prev = lock.try_take().ok();
+            lock.try_fill(1).ok();
+            prev
+        });
+    });
+}
+
+fn bench_once_cell_get(c: &mut Criterion) {
+    let cell = OnceCell::new(1u32);
+    c.bench_function("once_cell_get", |b| {
+        b.iter(|| black_box(&cell).get().copied());
+    });
+    let cell = OnceCell::<u32>::empty();
+    cell.get_or_init(|| 1);
+    c.bench_function("once_cell_get_or_init", |b| {
+        b.iter(|| *black_box(&cell).get_or_init(|| 2));
+    });
+    let lazy = Lazy::new(|| 1u32);
+    c.bench_function("lazy_deref", |b| {
+        b.iter(|| **black_box(&lazy));
+    });
+}
+
+// a raw atomic lock for comparison with the fast path
+fn bench_atomic_baseline(c: &mut Criterion) {
+    let state = AtomicU8::new(1);
+    c.bench_function("atomic_baseline", |b| {
+        b.iter(|| {
+            let state = black_box(&state);
+            if state.fetch_and(0, Ordering::Acquire) == 1 {
+                state.store(1, Ordering::Release);
+            }
+        });
+    });
+}
+
+#[cfg(feature = "bench")]
+criterion_group!(
+    benches,
+    bench_try_lock,
+    bench_try_get,
+    bench_try_lock_none,
+    bench_try_take,
+    bench_try_exchange,
+    bench_once_cell_get,
+    bench_atomic_baseline
+);
+#[cfg(feature = "bench")]
+criterion_main!(benches);
@@ -10,6 +10,12 @@ pub struct AsyncResult<T, E> {
     state: OptionLock<ResultState<T, E>>,
 }
 
+impl<T, E> Default for AsyncResult<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T, E> AsyncResult<T, E> {
     pub const fn new() -> Self {
         Self {
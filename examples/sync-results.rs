@@ -7,13 +7,12 @@ use std::{
     thread,
 };
 
-use option_lock::OptionLock;
+use option_lock::{OptionLock, OptionLockError};
 
 #[derive(Debug)]
 struct Results<T> {
     data: Vec<OptionLock<T>>,
     completed: AtomicUsize,
-    iter_index: usize,
 }
 
 impl<T> Results<T> {
@@ -23,7 +22,6 @@ impl<T> Results<T> {
         Self {
             data,
             completed: AtomicUsize::default(),
-            iter_index: 0,
         }
     }
 
@@ -32,25 +30,16 @@ impl<T> Results<T> {
     }
 
     pub fn return_result(&self, index: usize, value: T) {
-        if let Ok(()) = self.data[index].try_fill(value) {
-            self.completed.fetch_add(1, Ordering::Release);
-        } else {
-            panic!("Update conflict");
+        match OptionLock::fill_from_indexed(&self.data, Some((index, value))) {
+            Ok(count) => {
+                self.completed.fetch_add(count, Ordering::Release);
+            }
+            Err((index, _)) => panic!("Update conflict at index {}", index),
         }
     }
-}
-
-impl<T> Iterator for Results<T> {
-    type Item = T;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let idx = self.iter_index;
-        if idx < self.data.len() {
-            self.iter_index += 1;
-            Some(self.data[idx].take().unwrap())
-        } else {
-            None
-        }
+    pub fn take_all(&self) -> Result<Vec<T>, OptionLockError> {
+        OptionLock::try_take_all(&self.data)
     }
 }
 
@@ -69,11 +58,11 @@ fn main() {
         }
         spin_loop();
     }
-    let res = Arc::try_unwrap(res).expect("Error unwrapping arc");
-    let mut total = 0;
-    for item in res {
-        total += item;
-    }
+    let total: usize = res
+        .take_all()
+        .expect("Error taking results")
+        .into_iter()
+        .sum();
     assert_eq!(total, 90);
     println!("Completed");
 }
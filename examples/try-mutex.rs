@@ -1,6 +1,6 @@
-use std::{hint::spin_loop, sync::Arc, thread};
+use std::{sync::Arc, thread};
 
-use option_lock::Mutex;
+use option_lock::{Backoff, Mutex};
 
 // FIXME - this example would work equally well with a simple atomic
 
@@ -14,11 +14,16 @@ fn main() {
             *guard += 1;
         });
     }
+    let backoff = Backoff::new();
     loop {
         if shared.try_copy() == Ok(threads) {
             break;
         }
-        spin_loop()
+        if backoff.is_completed() {
+            thread::yield_now();
+        } else {
+            backoff.spin();
+        }
     }
     println!("Completed {} threads", threads);
 }
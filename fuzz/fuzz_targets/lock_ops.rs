@@ -0,0 +1,97 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use std::{
+    cell::Cell,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use option_lock::{MutexGuard, OptionGuard, OptionLock};
+
+thread_local! {
+    static LIVE: Cell<isize> = Cell::new(0);
+}
+
+// A value which tracks the number of live instances, and optionally
+// panics when it is dropped
+struct Tracked {
+    panic_on_drop: bool,
+}
+
+impl Tracked {
+    fn new(panic_on_drop: bool) -> Self {
+        LIVE.with(|live| live.set(live.get() + 1));
+        Self { panic_on_drop }
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        LIVE.with(|live| live.set(live.get() - 1));
+        if self.panic_on_drop && !std::thread::panicking() {
+            panic!("tracked value dropped");
+        }
+    }
+}
+
+fn run<'a>(lock: &'a OptionLock<Tracked>, guard: &mut Option<OptionGuard<'a, Tracked>>, op: u8) {
+    let panic_on_drop = op & 0x80 != 0;
+    match op & 0x0f {
+        0 => {
+            if guard.is_none() {
+                *guard = lock.try_lock().ok();
+            }
+        }
+        1 => {
+            guard.take();
+        }
+        2 => {
+            if let Some(guard) = guard.as_mut() {
+                guard.replace(Tracked::new(panic_on_drop));
+            }
+        }
+        3 => {
+            if let Some(guard) = guard.as_mut() {
+                guard.take();
+            }
+        }
+        4 => {
+            if let Some(guard) = guard.as_mut() {
+                guard.drop_value();
+            }
+        }
+        5 => {
+            let _ = lock.try_fill(Tracked::new(panic_on_drop));
+        }
+        6 => {
+            let _ = lock.try_take();
+        }
+        7 => {
+            let _ = lock.try_fill_with(|| Tracked::new(panic_on_drop));
+        }
+        8 => {
+            if let Ok(mut guard) = lock.try_get() {
+                drop(MutexGuard::replace(&mut guard, Tracked::new(panic_on_drop)));
+            }
+        }
+        _ => {}
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    LIVE.with(|live| live.set(0));
+    {
+        let lock = OptionLock::<Tracked>::empty();
+        let mut guard = None;
+        for &op in data {
+            let _ = catch_unwind(AssertUnwindSafe(|| run(&lock, &mut guard, op)));
+            assert_eq!(guard.is_some(), lock.is_locked());
+        }
+        drop(guard);
+        assert!(!lock.is_locked());
+        if let Some(mut value) = lock.into_inner() {
+            value.panic_on_drop = false;
+        }
+    }
+    LIVE.with(|live| assert_eq!(live.get(), 0));
+});
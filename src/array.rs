@@ -0,0 +1,87 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use super::lock::OptionLock;
+
+/// A fixed-capacity array of `OptionLock` slots, which may be used as an
+/// allocation-free set of work slots. The slots are available by `Deref`.
+///
+/// ```
+/// use option_lock::ArrayLock;
+///
+/// static SLOTS: ArrayLock<u32, 2> = ArrayLock::empty();
+///
+/// assert_eq!(SLOTS.try_fill_next(10), Ok(0));
+/// assert_eq!(SLOTS.try_fill_next(20), Ok(1));
+/// assert_eq!(SLOTS.try_fill_next(30), Err(30));
+/// assert_eq!(SLOTS.try_take_any(), Some((0, 10)));
+/// ```
+#[repr(transparent)]
+pub struct ArrayLock<T, const N: usize>([OptionLock<T>; N]);
+
+impl<T, const N: usize> ArrayLock<T, N> {
+    // only used to initialize the array of slots
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: OptionLock<T> = OptionLock::empty();
+
+    /// Create a new instance with no stored values.
+    pub const fn empty() -> Self {
+        Self([Self::EMPTY; N])
+    }
+
+    /// Try to store a value in the first empty slot which can be locked,
+    /// returning its index. If no such slot is found, then the value is
+    /// returned as an error.
+    pub fn try_fill_next(&self, mut value: T) -> Result<usize, T> {
+        for (index, slot) in self.0.iter().enumerate() {
+            match slot.try_fill(value) {
+                Ok(()) => return Ok(index),
+                Err(val) => value = val,
+            }
+        }
+        Err(value)
+    }
+
+    /// Try to take a value from the first filled slot which can be locked,
+    /// returning its index along with the value.
+    pub fn try_take_any(&self) -> Option<(usize, T)> {
+        self.0
+            .iter()
+            .enumerate()
+            .find_map(|(index, slot)| slot.try_take().ok().map(|value| (index, value)))
+    }
+
+    /// Unwrap an owned instance into the array of slots.
+    pub fn into_inner(self) -> [OptionLock<T>; N] {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Default for ArrayLock<T, N> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayLock<T, N> {
+    type Target = [OptionLock<T>; N];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> From<[OptionLock<T>; N]> for ArrayLock<T, N> {
+    fn from(slots: [OptionLock<T>; N]) -> Self {
+        Self(slots)
+    }
+}
+
+impl<T, const N: usize> Debug for ArrayLock<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArrayLock").field(&self.0).finish()
+    }
+}
@@ -0,0 +1,111 @@
+use core::{cell::Cell, hint::spin_loop};
+
+const SPIN_LIMIT: u32 = 6;
+
+/// Exponential backoff for spin loops.
+///
+/// Each call to `spin` issues an increasing number of `spin_loop` hints to the
+/// processor, up to a fixed limit. This does not depend on a timer or the
+/// thread scheduler, making it appropriate for `no_std` environments.
+///
+/// ```
+/// use option_lock::{Backoff, OptionLock};
+///
+/// let lock = OptionLock::new(1);
+/// let backoff = Backoff::new();
+/// let value = loop {
+///     if let Ok(value) = lock.try_take() {
+///         break value;
+///     }
+///     backoff.spin();
+/// };
+/// assert_eq!(value, 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct Backoff {
+    step: Cell<u32>,
+}
+
+impl Backoff {
+    /// Create a new `Backoff` instance.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { step: Cell::new(0) }
+    }
+
+    /// Reset the backoff to its initial state.
+    #[inline]
+    pub fn reset(&self) {
+        self.step.set(0);
+    }
+
+    /// Back off in a spin loop, increasing the number of iterations on each
+    /// call until the limit is reached.
+    #[inline]
+    pub fn spin(&self) {
+        let step = self.step.get();
+        for _ in 0..1 << step.min(SPIN_LIMIT) {
+            spin_loop();
+        }
+        if step <= SPIN_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Back off in a spin loop for a random number of iterations, bounded by
+    /// a limit which increases on each call.
+    #[cfg(feature = "jitter")]
+    #[inline]
+    pub fn spin_jitter(&self, rng: &mut impl BackoffRng) {
+        let step = self.step.get();
+        let limit = 1u32 << step.min(SPIN_LIMIT);
+        for _ in 0..=rng.next_u32() % limit {
+            spin_loop();
+        }
+        if step <= SPIN_LIMIT {
+            self.step.set(step + 1);
+        }
+    }
+
+    /// Check if the backoff has reached its limit. At this point the caller
+    /// may prefer to yield the current thread or park it, if supported.
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.step.get() > SPIN_LIMIT
+    }
+}
+
+/// A source of random numbers for randomized backoff.
+#[cfg(feature = "jitter")]
+pub trait BackoffRng {
+    /// Generate the next random value.
+    fn next_u32(&mut self) -> u32;
+}
+
+/// A minimal xorshift random number generator, suitable for adding jitter
+/// to backoff loops. This is not appropriate for other uses.
+#[cfg(feature = "jitter")]
+#[derive(Debug, Clone)]
+pub struct XorShiftRng(u32);
+
+#[cfg(feature = "jitter")]
+impl XorShiftRng {
+    /// Create a new generator from a seed value.
+    pub const fn new(seed: u32) -> Self {
+        // the state must be non-zero
+        Self(if seed == 0 { 0x9e37_79b9 } else { seed })
+    }
+}
+
+#[cfg(feature = "jitter")]
+impl BackoffRng for XorShiftRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+}
@@ -0,0 +1,66 @@
+use alloc::boxed::Box;
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use super::lock::OptionLock;
+
+/// An `OptionLock` around a boxed value.
+///
+/// Exchanging values only moves the pointers, avoiding a copy of a large
+/// payload. The methods of the inner `OptionLock` are available by `Deref`.
+#[repr(transparent)]
+pub struct BoxOptionLock<T>(OptionLock<Box<T>>);
+
+impl<T> BoxOptionLock<T> {
+    /// Create a new instance with no stored value.
+    pub const fn empty() -> Self {
+        Self(OptionLock::empty())
+    }
+
+    /// Create a new populated instance.
+    pub const fn new(value: Box<T>) -> Self {
+        Self(OptionLock::new(value))
+    }
+
+    /// Try to acquire an exclusive lock and exchange the stored value,
+    /// returning the previous value, if any. If the lock cannot be acquired
+    /// then the new value is returned as an error.
+    #[inline]
+    pub fn try_swap(&self, value: Box<T>) -> Result<Option<Box<T>>, Box<T>> {
+        self.0.try_exchange(value)
+    }
+
+    /// Unwrap an owned lock instance.
+    pub fn into_inner(self) -> Option<Box<T>> {
+        self.0.into_inner()
+    }
+}
+
+impl<T> Default for BoxOptionLock<T> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T> Deref for BoxOptionLock<T> {
+    type Target = OptionLock<Box<T>>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> From<Box<T>> for BoxOptionLock<T> {
+    fn from(value: Box<T>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Debug for BoxOptionLock<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "BoxOptionLock({:?})", &self.0.state)
+    }
+}
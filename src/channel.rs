@@ -0,0 +1,124 @@
+//! A single-use channel built on an `Arc<OptionLock>`.
+
+use alloc::sync::Arc;
+use core::{
+    fmt::{self, Debug, Formatter},
+    sync::atomic::{fence, Ordering},
+};
+
+#[cfg(feature = "std")]
+use super::backoff::Backoff;
+#[cfg(any(feature = "std", feature = "async"))]
+use super::error::RecvError;
+#[cfg(feature = "async")]
+use core::{future::poll_fn, task::Poll};
+
+use super::{
+    error::{OptionLockError, TryRecvError},
+    lock::OptionLock,
+};
+
+/// Create a channel for sending a single value between threads.
+///
+/// ```
+/// let (sender, receiver) = option_lock::channel::oneshot();
+/// std::thread::spawn(move || sender.send(5).unwrap())
+///     .join()
+///     .unwrap();
+/// assert_eq!(receiver.try_recv(), Ok(5));
+/// ```
+pub fn oneshot<T>() -> (Sender<T>, Receiver<T>) {
+    let lock = Arc::new(OptionLock::empty());
+    (Sender { lock: lock.clone() }, Receiver { lock })
+}
+
+/// The sending half of a [`oneshot`] channel.
+pub struct Sender<T> {
+    lock: Arc<OptionLock<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send a value to the receiver. If the receiver has been dropped then
+    /// the value is returned as an error.
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(value);
+        }
+        // the receiver never locks an empty slot, so this cannot be contended
+        self.lock.try_fill(value)
+    }
+
+    /// Check if the receiver has been dropped.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        Arc::strong_count(&self.lock) == 1
+    }
+}
+
+impl<T> Debug for Sender<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Sender").field(&self.lock).finish()
+    }
+}
+
+/// The receiving half of a [`oneshot`] channel.
+pub struct Receiver<T> {
+    lock: Arc<OptionLock<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Try to receive the sent value without waiting.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match self.lock.try_take() {
+            Ok(value) => Ok(value),
+            Err(OptionLockError::FillState) if Arc::strong_count(&self.lock) == 1 => {
+                // synchronize with the sender being dropped, which may
+                // have sent a value first
+                fence(Ordering::Acquire);
+                self.lock.try_take().map_err(|_| TryRecvError::Disconnected)
+            }
+            Err(_) => Err(TryRecvError::Empty),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    /// Wait for the sent value, spinning and then yielding to the scheduler.
+    pub fn recv(&self) -> Result<T, RecvError> {
+        let backoff = Backoff::new();
+        loop {
+            match self.try_recv() {
+                Ok(value) => return Ok(value),
+                Err(TryRecvError::Disconnected) => return Err(RecvError),
+                Err(TryRecvError::Empty) => {
+                    if backoff.is_completed() {
+                        std::thread::yield_now();
+                    } else {
+                        backoff.spin();
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    /// Wait for the sent value in an async context. The task is woken
+    /// immediately to poll again while the value is not available.
+    #[clippy::msrv = "1.64"]
+    pub async fn recv_async(&self) -> Result<T, RecvError> {
+        poll_fn(|cx| match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+impl<T> Debug for Receiver<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Receiver").field(&self.lock).finish()
+    }
+}
@@ -1,4 +1,4 @@
-use core::fmt::{self, Display, Formatter};
+use core::fmt::{self, Debug, Display, Formatter};
 
 /// Error returned by failing try-lock operations
 #[derive(Debug, PartialEq, Eq)]
@@ -42,6 +42,22 @@ impl Display for MutexLockError {
 #[cfg(feature = "std")]
 impl ::std::error::Error for MutexLockError {}
 
+impl From<OptionLockError> for MutexLockError {
+    /// An empty mutex is treated as poisoned.
+    fn from(err: OptionLockError) -> Self {
+        match err {
+            OptionLockError::FillState => Self::Poisoned,
+            OptionLockError::Unavailable => Self::Unavailable,
+        }
+    }
+}
+
+impl From<PoisonError> for MutexLockError {
+    fn from(_: PoisonError) -> Self {
+        Self::Poisoned
+    }
+}
+
 /// Error returned when a lock has been poisoned
 #[derive(Debug, PartialEq, Eq)]
 pub struct PoisonError;
@@ -54,3 +70,58 @@ impl Display for PoisonError {
 
 #[cfg(feature = "std")]
 impl ::std::error::Error for PoisonError {}
+
+/// Error returned by a failing `OnceCell::get_or_try_init_once` operation
+#[derive(Debug, PartialEq, Eq)]
+pub enum InitError<E> {
+    /// The initializer returned an error
+    Failed(E),
+    /// A previous initialization attempt failed
+    Poisoned,
+}
+
+impl<E: Display> Display for InitError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Failed(err) => write!(f, "InitError(Failed({}))", err),
+            Self::Poisoned => f.write_str("InitError(Poisoned)"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug + Display> ::std::error::Error for InitError<E> {}
+
+/// Error returned by a failing `Receiver::try_recv` operation
+#[derive(Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// No value has been sent yet
+    Empty,
+    /// The sender was dropped without sending a value
+    Disconnected,
+}
+
+impl Display for TryRecvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Empty => "TryRecvError(Empty)",
+            Self::Disconnected => "TryRecvError(Disconnected)",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for TryRecvError {}
+
+/// Error returned when the sender of a channel was dropped without sending a value
+#[derive(Debug, PartialEq, Eq)]
+pub struct RecvError;
+
+impl Display for RecvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("RecvError")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for RecvError {}
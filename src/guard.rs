@@ -0,0 +1,48 @@
+use core::ops::DerefMut;
+
+use super::mutex::MutexGuard;
+#[cfg(feature = "alloc")]
+use super::{
+    mutex::Mutex,
+    owned::{CloneableHandle, MappedOwnedMutexGuard, OwnedMutexGuard},
+};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// An exclusive guard over a value of type `T`, which releases the lock
+/// when dropped. This trait is implemented by each of the guard types which
+/// dereference to a contained value, allowing functions to accept
+/// `impl LockGuard<T>`. It cannot be implemented outside of this crate.
+///
+/// ```
+/// use option_lock::{LockGuard, Mutex};
+/// use std::sync::Arc;
+///
+/// fn increment(mut guard: impl LockGuard<i32>) {
+///     *guard += 1;
+/// }
+///
+/// let mutex = Arc::new(Mutex::new(0));
+/// increment(mutex.try_lock().unwrap());
+/// increment(mutex.try_lock_arc().unwrap());
+/// assert_eq!(mutex.try_copy(), Ok(2));
+/// ```
+pub trait LockGuard<T>: DerefMut<Target = T> + sealed::Sealed {}
+
+impl<T> sealed::Sealed for MutexGuard<'_, T> {}
+impl<T> LockGuard<T> for MutexGuard<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T, P: CloneableHandle<Target = Mutex<T>>> sealed::Sealed for OwnedMutexGuard<T, P> {}
+#[cfg(feature = "alloc")]
+impl<T, P: CloneableHandle<Target = Mutex<T>>> LockGuard<T> for OwnedMutexGuard<T, P> {}
+
+#[cfg(feature = "alloc")]
+impl<T, U, P: CloneableHandle<Target = Mutex<T>>> sealed::Sealed
+    for MappedOwnedMutexGuard<T, U, P>
+{
+}
+#[cfg(feature = "alloc")]
+impl<T, U, P: CloneableHandle<Target = Mutex<T>>> LockGuard<U> for MappedOwnedMutexGuard<T, U, P> {}
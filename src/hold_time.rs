@@ -0,0 +1,75 @@
+use std::{
+    cell::UnsafeCell,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+struct HoldStats {
+    last: Option<Duration>,
+    warn_after: Option<(Duration, fn(Duration))>,
+}
+
+/// Records how long a lock is held by each guard.
+pub(crate) struct HoldTimer {
+    /// Only accessed by the holder of the lock
+    acquired: UnsafeCell<Option<Instant>>,
+    stats: Mutex<HoldStats>,
+}
+
+impl HoldTimer {
+    // the `track_hold_time` feature requires a `const` std `Mutex`
+    #[clippy::msrv = "1.63"]
+    pub const fn new() -> Self {
+        Self {
+            acquired: UnsafeCell::new(None),
+            stats: Mutex::new(HoldStats {
+                last: None,
+                warn_after: None,
+            }),
+        }
+    }
+
+    fn stats(&self) -> std::sync::MutexGuard<'_, HoldStats> {
+        self.stats.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Record the time the lock was acquired, unless it is already recorded
+    /// for an owned guard which is being released.
+    ///
+    /// # Safety
+    /// The lock must be held by the caller.
+    #[inline]
+    pub unsafe fn start(&self) {
+        // SAFETY: the lock is held by the caller
+        let acquired = unsafe { &mut *self.acquired.get() };
+        if acquired.is_none() {
+            *acquired = Some(Instant::now());
+        }
+    }
+
+    /// Record the duration for which the lock was held.
+    ///
+    /// # Safety
+    /// The lock must be held by the caller, and released afterward.
+    #[inline]
+    pub unsafe fn end(&self) {
+        // SAFETY: the lock is held by the caller
+        if let Some(acquired) = unsafe { &mut *self.acquired.get() }.take() {
+            let held = acquired.elapsed();
+            let mut stats = self.stats();
+            stats.last = Some(held);
+            if let Some((_, warn)) = stats.warn_after.filter(|(limit, _)| held > *limit) {
+                drop(stats);
+                warn(held);
+            }
+        }
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.stats().last
+    }
+
+    pub fn warn_after(&self, limit: Duration, warn: fn(Duration)) {
+        self.stats().warn_after = Some((limit, warn));
+    }
+}
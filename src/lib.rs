@@ -29,30 +29,102 @@
 //!
 //! There are additional examples in the code repository.
 //!
+//! When compiled with the `std` feature, a `MutexGuard` which is dropped while
+//! the thread is panicking will drop the contained value, poisoning the mutex.
+//! The `no_poison` feature disables this behavior, in which case the value may
+//! be observed in an inconsistent state after a panic during its mutation.
+//!
+//! The `debug_reentrancy` feature tracks the thread holding each borrowed
+//! guard in debug builds, so that `spin_lock` and `spin_get` panic when called
+//! by that thread instead of spinning forever. While the feature is active,
+//! borrowed guards such as `OptionGuard` and `MutexGuard` are not `Send`, so
+//! that the recorded thread remains accurate. Owned guards are not tracked.
+//!
+//! The `ticket` feature adds `OptionLock::spin_lock_fair`, which acquires the
+//! lock in order of arrival. This adds two atomic `u16` ticket counters to each
+//! lock, increasing its size by four bytes (before alignment).
+//!
+//! The `track_hold_time` feature records how long each lock is held by a
+//! guard, reported by `OptionLock::last_hold_duration`, for diagnosing long
+//! critical sections.
+//!
+//! The `instrument` feature adds `OptionLock::with_observer`, which attaches a
+//! function to be notified of each state transition of the lock, for example
+//! to collect contention metrics.
+//!
 //! This crate uses `unsafe` code blocks. It is `no_std`-compatible when compiled
 //! without the `std` feature.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![deny(missing_docs, missing_debug_implementations, rust_2018_idioms)]
+#![forbid(unsafe_op_in_unsafe_fn)]
 
 #[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
 
+mod backoff;
+pub use self::backoff::Backoff;
+#[cfg(feature = "jitter")]
+pub use self::backoff::{BackoffRng, XorShiftRng};
+
 mod error;
-pub use self::error::OptionLockError;
+pub use self::error::{
+    InitError, MutexLockError, OptionLockError, PoisonError, RecvError, TryRecvError,
+};
+
+mod guard;
+pub use self::guard::LockGuard;
 
 mod lock;
 
-pub use self::lock::{OptionGuard, OptionLock};
+#[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+mod reentrancy;
+
+#[cfg(feature = "track_hold_time")]
+mod hold_time;
+
+pub use self::lock::{
+    Drain, FillGuard, InitOutcome, OptionGuard, OptionLock, OwnedGuard, TryGetResult, TryLockResult,
+};
+
+mod array;
+pub use self::array::ArrayLock;
 
 #[cfg(feature = "alloc")]
-mod arc;
+mod owned;
+#[cfg(feature = "alloc")]
+pub use self::owned::{
+    CloneableHandle, MappedMutexGuardArc, MappedOwnedMutexGuard, MutexGuardArc, MutexGuardRc,
+    OptionGuardArc, OptionGuardRc, OwnedMutexGuard, OwnedOptionGuard,
+};
+
+#[cfg(feature = "alloc")]
+mod boxed;
+#[cfg(feature = "alloc")]
+pub use self::boxed::BoxOptionLock;
+
+#[cfg(feature = "async")]
+mod waker;
+#[cfg(feature = "async")]
+pub use self::waker::WakeSlot;
+
+#[cfg(feature = "alloc")]
+pub mod channel;
+
+pub mod state;
+#[cfg(feature = "instrument")]
+pub use self::state::{LockObserver, LockState};
+
+mod split;
+pub use self::split::{Filler, Taker};
 #[cfg(feature = "alloc")]
-pub use self::arc::{MutexGuardArc, OptionGuardArc};
+pub use self::split::{FillerArc, TakerArc};
 
 mod once;
-pub use self::once::{Lazy, OnceCell};
+#[cfg(feature = "async")]
+pub use self::once::OnceCellWait;
+pub use self::once::{Lazy, LazyLock, OnceCell};
 
 mod mutex;
-pub use self::mutex::{Mutex, MutexGuard};
+pub use self::mutex::{Mutex, MutexGuard, MutexLockResult, PinnedMutexGuard, StaticMutexGuard};
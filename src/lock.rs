@@ -2,29 +2,66 @@ use core::{
     cell::UnsafeCell,
     fmt::{self, Debug, Formatter},
     hint::spin_loop,
-    mem::{self, transmute, ManuallyDrop, MaybeUninit},
-    ops::Deref,
+    mem::{self, ManuallyDrop, MaybeUninit},
+    ops::{ControlFlow, Deref},
     ptr::drop_in_place,
     sync::atomic::{AtomicU8, Ordering},
 };
 
+#[cfg(feature = "ticket")]
+use core::sync::atomic::AtomicU32;
+
+#[cfg(all(feature = "jitter", not(feature = "ticket")))]
+use core::sync::atomic::AtomicU32;
+
+#[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+use core::marker::PhantomData;
+
 #[cfg(feature = "alloc")]
-use alloc::sync::Arc;
+use alloc::{rc::Rc, sync::Arc, vec::Vec};
+
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
 
 #[cfg(feature = "alloc")]
-use super::arc::{MutexGuardArc, OptionGuardArc};
+use super::owned::{MutexGuardArc, MutexGuardRc, OptionGuardArc, OptionGuardRc};
+
+use super::backoff::Backoff;
+#[cfg(feature = "jitter")]
+use super::backoff::{BackoffRng, XorShiftRng};
 
 use super::error::OptionLockError;
+use super::state;
+#[cfg(feature = "instrument")]
+use super::state::{LockObserver, LockState};
+
+#[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+use super::reentrancy::Owner;
+
+#[cfg(feature = "track_hold_time")]
+use super::hold_time::HoldTimer;
+
+use super::split::{Filler, Taker};
+#[cfg(feature = "alloc")]
+use super::split::{FillerArc, TakerArc};
 
-use super::mutex::MutexGuard;
+#[cfg(feature = "alloc")]
+use super::mutex::Mutex;
+use super::mutex::{MutexGuard, PinnedMutexGuard};
 
 #[repr(transparent)]
 pub(crate) struct State(AtomicU8);
 
 impl State {
-    pub const FREE: u8 = 1 << 0;
-    pub const SOME: u8 = 1 << 1;
-    pub const AVAILABLE: u8 = Self::FREE | Self::SOME;
+    pub const FREE: u8 = state::FREE;
+    pub const SOME: u8 = state::SOME;
+    pub const AVAILABLE: u8 = state::AVAILABLE;
+    /// Only used by `OnceCell`, to record a failed initialization
+    pub const POISON: u8 = 1 << 2;
+    pub const POISONED: u8 = Self::FREE | Self::POISON;
+    /// Only used by `OnceCell`, to record that a thread is waiting for the
+    /// lock to be released
+    pub const WAITING: u8 = 1 << 3;
 
     pub const fn new(value: u8) -> Self {
         Self(AtomicU8::new(value))
@@ -39,6 +76,15 @@ impl State {
     pub fn value(&self) -> u8 {
         self.0.load(Ordering::Relaxed)
     }
+
+    pub fn name(&self) -> &'static str {
+        match self.value() {
+            Self::FREE => "None",
+            Self::AVAILABLE => "Some",
+            Self::POISONED => "Poisoned",
+            _ => "Locked",
+        }
+    }
 }
 
 impl Deref for State {
@@ -52,11 +98,7 @@ impl Deref for State {
 
 impl Debug for State {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(match self.0.load(Ordering::Relaxed) {
-            Self::FREE => "None",
-            Self::AVAILABLE => "Some",
-            _ => "Locked",
-        })
+        f.write_str(self.name())
     }
 }
 
@@ -67,12 +109,101 @@ impl PartialEq<u8> for State {
     }
 }
 
+// The error paths of the try-lock operations are kept out of line so that
+// the successful path can be inlined into the caller.
+
+#[cold]
+#[inline(never)]
+fn unavailable_error() -> OptionLockError {
+    OptionLockError::Unavailable
+}
+
+#[cold]
+#[inline(never)]
+fn fill_state_error(state: u8, unlocked_state: u8) -> OptionLockError {
+    if state == unlocked_state {
+        OptionLockError::FillState
+    } else {
+        OptionLockError::Unavailable
+    }
+}
+
+/// The result of a `try_lock` operation on an [`OptionLock`].
+pub type TryLockResult<'a, T> = Result<OptionGuard<'a, T>, OptionLockError>;
+
+/// The result of a `try_get` operation on an [`OptionLock`].
+pub type TryGetResult<'a, T> = Result<MutexGuard<'a, T>, OptionLockError>;
+
+/// The result of a `try_init_slot` operation on an [`OptionLock`].
+#[derive(Debug)]
+pub enum InitOutcome<'a, T> {
+    /// The slot was filled by the current caller
+    Won(MutexGuard<'a, T>),
+    /// The slot was already filled
+    AlreadyFilled,
+    /// The lock could not be acquired
+    Contended,
+}
+
 /// A read/write lock around an `Option` value.
+///
+/// The lock consists of the storage for a value of type `T` and a single
+/// atomic byte, so its size is that of `T` plus one byte, rounded up to the
+/// alignment of `T`. The `ticket` feature adds a four-byte counter, so that
+/// the size is that of `T` plus five bytes, rounded up to the alignment of `T`
+/// or four, whichever is greater. The `debug_reentrancy` (in debug builds),
+/// `track_hold_time` and `instrument` features add further fields to the lock.
+///
+/// For a zero-sized type such as `()` the lock occupies a single byte, and
+/// reading or writing the value compiles to nothing, so `OptionLock<()>` may
+/// be used as a simple flag or raw lock.
 pub struct OptionLock<T> {
     data: UnsafeCell<MaybeUninit<T>>,
     pub(crate) state: State,
+    #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+    owner: Owner,
+    /// The next ticket to be issued in the upper half, and the ticket now
+    /// being served in the lower half
+    #[cfg(feature = "ticket")]
+    tickets: AtomicU32,
+    #[cfg(feature = "track_hold_time")]
+    hold: HoldTimer,
+    #[cfg(feature = "instrument")]
+    observer: Option<LockObserver>,
 }
 
+// Check the documented layout of the lock for some common value types.
+#[cfg(not(any(
+    all(feature = "debug_reentrancy", debug_assertions),
+    feature = "track_hold_time",
+    feature = "instrument"
+)))]
+const _: () = {
+    use core::mem::{align_of, size_of};
+
+    const fn expected_size<T>() -> usize {
+        // one byte is added for the state, and four more for the ticket
+        // counter if enabled, rounded up to the alignment
+        let (extra, align) = if cfg!(feature = "ticket") && align_of::<T>() < 4 {
+            (5, 4)
+        } else if cfg!(feature = "ticket") {
+            (5, align_of::<T>())
+        } else {
+            (1, align_of::<T>())
+        };
+        (size_of::<T>() + extra + align - 1) / align * align
+    }
+
+    assert!(size_of::<OptionLock<()>>() == expected_size::<()>());
+    assert!(size_of::<OptionLock<u8>>() == expected_size::<u8>());
+    assert!(size_of::<OptionLock<u16>>() == expected_size::<u16>());
+    assert!(size_of::<OptionLock<u32>>() == expected_size::<u32>());
+    assert!(size_of::<OptionLock<u64>>() == expected_size::<u64>());
+    assert!(size_of::<OptionLock<usize>>() == expected_size::<usize>());
+    assert!(size_of::<OptionLock<[u8; 3]>>() == expected_size::<[u8; 3]>());
+    assert!(align_of::<OptionLock<u64>>() == align_of::<u64>());
+};
+
 impl<T> Default for OptionLock<T> {
     fn default() -> Self {
         Self::empty()
@@ -88,6 +219,14 @@ impl<T> OptionLock<T> {
         Self {
             data: UnsafeCell::new(MaybeUninit::uninit()),
             state: State::new(State::FREE),
+            #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+            owner: Owner::new(),
+            #[cfg(feature = "ticket")]
+            tickets: AtomicU32::new(0),
+            #[cfg(feature = "track_hold_time")]
+            hold: HoldTimer::new(),
+            #[cfg(feature = "instrument")]
+            observer: None,
         }
     }
 
@@ -96,17 +235,221 @@ impl<T> OptionLock<T> {
         Self {
             data: UnsafeCell::new(MaybeUninit::new(value)),
             state: State::new(State::AVAILABLE),
+            #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+            owner: Owner::new(),
+            #[cfg(feature = "ticket")]
+            tickets: AtomicU32::new(0),
+            #[cfg(feature = "track_hold_time")]
+            hold: HoldTimer::new(),
+            #[cfg(feature = "instrument")]
+            observer: None,
+        }
+    }
+
+    /// Create an array of lock instances, calling the provided function with
+    /// each index to determine the initial value of the slot.
+    pub fn new_array<const N: usize>(mut f: impl FnMut(usize) -> Option<T>) -> [Self; N] {
+        // the slots initialized so far are dropped if the function panics
+        struct Partial<T, const N: usize> {
+            slots: [MaybeUninit<OptionLock<T>>; N],
+            len: usize,
+        }
+
+        impl<T, const N: usize> Drop for Partial<T, N> {
+            fn drop(&mut self) {
+                for slot in &mut self.slots[..self.len] {
+                    // SAFETY: the first `len` slots are initialized
+                    unsafe { drop_in_place(slot.as_mut_ptr()) };
+                }
+            }
+        }
+
+        let mut partial = Partial::<T, N> {
+            // SAFETY: an array of `MaybeUninit` does not require initialization
+            slots: unsafe { MaybeUninit::<[MaybeUninit<Self>; N]>::uninit().assume_init() },
+            len: 0,
+        };
+        while partial.len < N {
+            partial.slots[partial.len] = MaybeUninit::new(Self::from(f(partial.len)));
+            partial.len += 1;
+        }
+        let partial = ManuallyDrop::new(partial);
+        // SAFETY: every slot is initialized, and `MaybeUninit<Self>` has the
+        // same layout as `Self`
+        unsafe { (&partial.slots as *const [MaybeUninit<Self>; N] as *const [Self; N]).read() }
+    }
+
+    /// Record the current thread as the holder of a borrowed guard.
+    #[inline]
+    pub(crate) fn owner_acquire(&self) {
+        #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+        self.owner.acquire();
+    }
+
+    /// Clear the holder of a borrowed guard.
+    #[inline]
+    pub(crate) fn owner_release(&self) {
+        #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+        self.owner.release();
+    }
+
+    /// Record the time at which the lock was acquired by a guard.
+    ///
+    /// # Safety
+    /// The lock must be held by the caller.
+    #[inline]
+    pub(crate) unsafe fn hold_start(&self) {
+        // SAFETY: upheld by the caller
+        #[cfg(feature = "track_hold_time")]
+        unsafe {
+            self.hold.start()
+        };
+    }
+
+    /// Record the duration for which the lock was held by a guard.
+    ///
+    /// # Safety
+    /// The lock must be held by the caller, and released afterward.
+    #[inline]
+    pub(crate) unsafe fn hold_end(&self) {
+        // SAFETY: upheld by the caller
+        #[cfg(feature = "track_hold_time")]
+        unsafe {
+            self.hold.end()
+        };
+    }
+
+    #[cfg(feature = "instrument")]
+    /// Attach an observer which is notified of each state transition of the
+    /// lock. See [`LockState`] for the transitions which are reported.
+    pub fn with_observer(mut self, observer: LockObserver) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Report a state transition to the observer, if any.
+    #[inline]
+    pub(crate) fn observe(&self, from: u8, to: u8) {
+        #[cfg(feature = "instrument")]
+        if let Some(observer) = self.observer {
+            observer(LockState::from_raw(from), LockState::from_raw(to));
         }
+        #[cfg(not(feature = "instrument"))]
+        let _ = (from, to);
+    }
+
+    #[cfg(feature = "track_hold_time")]
+    /// Get the duration for which the lock was most recently held by a guard.
+    pub fn last_hold_duration(&self) -> Option<::std::time::Duration> {
+        self.hold.last()
+    }
+
+    #[cfg(feature = "track_hold_time")]
+    /// Call a function with the hold duration whenever a guard for this lock
+    /// is released after being held for longer than the given limit.
+    ///
+    /// The function is called by the thread releasing the guard, before the
+    /// lock is released, so it must not attempt to acquire the same lock.
+    pub fn warn_if_held_longer_than(
+        &self,
+        limit: ::std::time::Duration,
+        warn: fn(::std::time::Duration),
+    ) {
+        self.hold.warn_after(limit, warn);
+    }
+
+    /// Panic if the current thread would deadlock waiting on its own guard.
+    #[inline]
+    fn check_reentrant(&self) {
+        #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+        self.owner.check();
+    }
+
+    #[inline]
+    fn as_mut_ptr(&self) -> *mut T {
+        self.data.get().cast()
+    }
+
+    /// Check that the slot is not read while it is known to be empty. When a
+    /// guard is held, the guard tracks the fill state instead of the lock.
+    #[inline]
+    fn debug_assert_readable(&self) {
+        debug_assert!(
+            self.is_some() || self.is_locked(),
+            "Attempted to read from an empty OptionLock"
+        );
+    }
+
+    /// Obtain a shared reference to the contained value.
+    ///
+    /// # Safety
+    /// The slot must contain a value, and no exclusive reference to the value
+    /// may exist for the lifetime of the returned reference.
+    #[inline]
+    pub(crate) unsafe fn value_ref(&self) -> &T {
+        self.debug_assert_readable();
+        // SAFETY: the value is initialized and not exclusively borrowed
+        unsafe { &*self.as_mut_ptr() }
+    }
+
+    /// Obtain an exclusive reference to the contained value.
+    ///
+    /// # Safety
+    /// The slot must contain a value, and no other reference to the value may
+    /// exist for the lifetime of the returned reference.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub(crate) unsafe fn value_mut(&self) -> &mut T {
+        self.debug_assert_readable();
+        // SAFETY: the value is initialized and not otherwise borrowed
+        unsafe { &mut *self.as_mut_ptr() }
+    }
+
+    /// Move the contained value out of the slot, leaving it uninitialized.
+    ///
+    /// # Safety
+    /// The slot must contain a value which is not borrowed, and the caller
+    /// must record that the slot is now empty.
+    #[inline]
+    pub(crate) unsafe fn read_value(&self) -> T {
+        self.debug_assert_readable();
+        // SAFETY: the value is initialized and not borrowed
+        unsafe { self.as_mut_ptr().read() }
+    }
+
+    /// Move a value into the slot without dropping any previous value.
+    ///
+    /// # Safety
+    /// The slot must be exclusively accessed by the caller, and the caller
+    /// must record that the slot is now filled.
+    #[inline]
+    pub(crate) unsafe fn write_value(&self, value: T) {
+        // SAFETY: the slot is exclusively accessed
+        unsafe { self.as_mut_ptr().write(value) }
     }
 
+    /// Drop the contained value in place, leaving the slot uninitialized.
+    ///
+    /// # Safety
+    /// The slot must contain a value which is not borrowed, and the caller
+    /// must record that the slot is now empty.
     #[inline]
-    pub(crate) unsafe fn as_ptr(&self) -> *const T {
-        (&*self.data.get()).as_ptr()
+    pub(crate) unsafe fn drop_value(&self) {
+        self.debug_assert_readable();
+        // SAFETY: the value is initialized and not borrowed
+        unsafe { drop_in_place(self.as_mut_ptr()) }
     }
 
+    /// Obtain a formatter for the lock which shows the contained value, if any.
+    ///
+    /// The lock is briefly acquired while formatting the value. If the lock is
+    /// already held then the value is not shown.
     #[inline]
-    pub(crate) unsafe fn as_mut_ptr(&self) -> *mut T {
-        (&mut *self.data.get()).as_mut_ptr()
+    pub fn debug_value(&self) -> impl Debug + '_
+    where
+        T: Debug,
+    {
+        DebugValue(self)
     }
 
     /// Check if there is no stored value and no guard held.
@@ -121,11 +464,47 @@ impl<T> OptionLock<T> {
         self.state == State::AVAILABLE
     }
 
+    /// Check if there is a stored value and no guard held, using an `Acquire`
+    /// load of the lock state.
+    ///
+    /// The other state checks use `Relaxed` loads, which are sufficient for
+    /// deciding whether to retry a lock operation but do not synchronize with
+    /// the thread which filled the lock. When the value is going to be read
+    /// without acquiring the lock, such as by `get_unchecked`, this method must
+    /// be used so that the stored value is visible to the current thread.
+    #[inline]
+    pub fn is_some_unlocked_acquire(&self) -> bool {
+        self.state.load(Ordering::Acquire) == State::AVAILABLE
+    }
+
     #[inline]
     pub(crate) fn is_some(&self) -> bool {
         self.state.value() & State::SOME != 0
     }
 
+    /// Check if there is a stored value using an `Acquire` load, so that the
+    /// value written by the thread which filled the lock is visible.
+    #[inline]
+    pub(crate) fn is_some_acquire(&self) -> bool {
+        self.state.load(Ordering::Acquire) & State::SOME != 0
+    }
+
+    /// Get a name for the current state of the lock: one of `"None"`, `"Some"`
+    /// or `"Locked"`, as shown by the `Debug` implementation.
+    ///
+    /// The lock within a `OnceCell` may also be named `"Poisoned"` after its
+    /// initializer fails, but that lock is not exposed by the cell.
+    #[inline]
+    pub fn state_name(&self) -> &'static str {
+        self.state.name()
+    }
+
+    /// Check if two references point to the same lock instance.
+    #[inline]
+    pub fn ptr_eq(&self, other: &OptionLock<T>) -> bool {
+        core::ptr::eq(self, other)
+    }
+
     /// Check if a guard is held.
     #[inline]
     pub fn is_locked(&self) -> bool {
@@ -135,66 +514,373 @@ impl<T> OptionLock<T> {
     /// Get a mutable reference to the contained value, if any.
     pub fn get_mut(&mut self) -> Option<&mut T> {
         if self.is_some() {
-            Some(unsafe { &mut *self.as_mut_ptr() })
+            // SAFETY: the lock is exclusively borrowed and contains a value
+            Some(unsafe { self.value_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the contained value, storing the provided
+    /// value first if the lock is empty.
+    #[inline]
+    pub fn get_mut_or_insert(&mut self, value: T) -> &mut T {
+        self.get_mut_or_insert_with(|| value)
+    }
+
+    /// Get a mutable reference to the contained value, storing the result of
+    /// the initializer function first if the lock is empty.
+    pub fn get_mut_or_insert_with(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        if !self.state.is_some_mut() {
+            // SAFETY: the lock is exclusively borrowed and empty
+            unsafe { self.write_value(f()) };
+            *self.state.0.get_mut() |= State::SOME;
+        }
+        // SAFETY: the lock is exclusively borrowed and contains a value
+        unsafe { self.value_mut() }
+    }
+
+    /// Obtain a guard for an exclusively borrowed lock. Ownership of the lock
+    /// ensures exclusive access, so no atomic operations are performed.
+    #[inline]
+    pub fn as_guard_mut(&mut self) -> OwnedGuard<'_, T> {
+        OwnedGuard { lock: self }
+    }
+
+    /// Get a shared reference to the contained value without checking the
+    /// state of the lock.
+    ///
+    /// # Safety
+    /// The lock must contain a value, and no guard may be held (or acquired)
+    /// for the lifetime of the returned reference. In debug builds this will
+    /// panic if the lock is known to be empty.
+    #[inline]
+    pub unsafe fn get_unchecked(&self) -> &T {
+        // SAFETY: upheld by the caller
+        unsafe { self.value_ref() }
+    }
+
+    /// Get a mutable reference to the contained value without checking the
+    /// state of the lock.
+    ///
+    /// # Safety
+    /// The lock must contain a value, and no guard may be held (or acquired)
+    /// and no other reference to the value may exist for the lifetime of the
+    /// returned reference.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut_unchecked(&self) -> &mut T {
+        // SAFETY: upheld by the caller
+        unsafe { self.value_mut() }
+    }
+
+    /// Get a shared reference to the contained value, first storing the result
+    /// of the initializer function if the lock is empty. If the lock is held
+    /// by another thread, then this spins until it is released. Like a
+    /// `OnceCell`, the value may then be read directly without locking.
+    ///
+    /// # Safety
+    /// The returned reference is not protected by the lock. No guard may be
+    /// acquired for a filled lock while the reference is in use, so the value
+    /// must not be taken, replaced, or modified by any other operation (such as
+    /// `try_take` or `try_lock`) for the lifetime of the reference. Concurrent
+    /// calls to this method are permitted, as they only lock an empty slot.
+    pub unsafe fn get_or_fill_ref(&self, f: impl FnOnce() -> T) -> &T {
+        // SAFETY: upheld by the caller
+        unsafe { self.get_or_init_reporting(f) }.0
+    }
+
+    /// Get a shared reference to the contained value, first storing the result
+    /// of the initializer function if the lock is empty. The returned flag is
+    /// `true` if the initializer was called by the current thread, which is
+    /// useful for recording cache hits and misses.
+    ///
+    /// # Safety
+    /// The same requirements apply as for [`OptionLock::get_or_fill_ref`].
+    pub unsafe fn get_or_init_reporting(&self, f: impl FnOnce() -> T) -> (&T, bool) {
+        let mut f = Some(f);
+        let backoff = Backoff::new();
+        loop {
+            // the acquire load synchronizes with the thread which stored the value
+            if self.is_some_unlocked_acquire() {
+                break;
+            }
+            if let Ok(mut guard) = self.try_lock_none() {
+                guard.replace((f.take().unwrap())());
+                break;
+            }
+            backoff.spin();
+        }
+        // SAFETY: the lock has been filled, and the value is not modified for
+        // the lifetime of the reference according to the caller
+        (unsafe { self.value_ref() }, f.is_none())
+    }
+
+    /// Copy the contained value without acquiring the lock, if the lock is
+    /// observed to be filled and unlocked. This is a best-effort snapshot for
+    /// monitoring purposes: the value may be taken or replaced immediately
+    /// after it is copied.
+    ///
+    /// # Safety
+    /// No other thread may modify the value while it is being copied. A guard
+    /// which is acquired concurrently must not write to the slot until this
+    /// method has returned.
+    #[inline]
+    pub unsafe fn peek_copy(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        // the acquire load synchronizes with the release of the lock by the
+        // thread which stored the value
+        if self.is_some_unlocked_acquire() {
+            // SAFETY: the value is initialized, and not modified concurrently
+            // according to the caller
+            Some(unsafe { self.as_mut_ptr().read() })
         } else {
             None
         }
     }
 
+    /// Drop the contained value, if any, leaving the lock empty. This is
+    /// equivalent to assigning `OptionLock::empty()`.
+    pub fn reset(&mut self) {
+        let value = if self.state.is_some_mut() {
+            // SAFETY: the lock is exclusively borrowed and contains a value
+            Some(unsafe { self.read_value() })
+        } else {
+            None
+        };
+        // the state is updated first in case the destructor panics
+        *self.state.0.get_mut() = State::FREE;
+        drop(value);
+    }
+
+    /// Reconstruct a lock from the result of `snapshot`. This is equivalent
+    /// to `OptionLock::from(value)`.
+    #[inline]
+    pub fn restore(value: Option<T>) -> Self {
+        Self::from(value)
+    }
+
     /// Unwrap an owned lock instance.
     pub fn into_inner(mut self) -> Option<T> {
         if self.state.is_some_mut() {
             let slf = ManuallyDrop::new(self);
-            Some(unsafe { slf.as_mut_ptr().read() })
+            // SAFETY: the lock is owned and contains a value, and is not dropped
+            Some(unsafe { slf.read_value() })
         } else {
             None
         }
     }
 
+    /// Convert an owned lock instance into a lock over another type, applying
+    /// `f` to the contained value if any. An empty lock remains empty.
+    pub fn map<U>(mut self, f: impl FnOnce(T) -> U) -> OptionLock<U> {
+        if self.state.is_some_mut() {
+            // SAFETY: the lock is owned and contains a value
+            let value = unsafe { self.read_value() };
+            // the value has been moved out, so it must not be dropped with the lock
+            *self.state.0.get_mut() &= !State::SOME;
+            OptionLock::new(f(value))
+        } else {
+            OptionLock::empty()
+        }
+    }
+
     /// In a spin loop, wait to get an exclusive lock on the contained value.
     pub fn spin_get(&self) -> MutexGuard<'_, T> {
+        self.check_reentrant();
+        let backoff = Backoff::new();
         loop {
             if let Ok(guard) = self.try_get() {
                 return guard;
             }
-            while !self.is_some_unlocked() {
-                spin_loop();
+            self.spin_until_with(&backoff, Self::is_some_unlocked);
+        }
+    }
+
+    /// In a spin loop, wait to get an exclusive lock on the contained value,
+    /// first storing the result of the initializer function if the lock is
+    /// empty. Only the thread which acquires the empty lock calls its
+    /// initializer, while others wait for the lock to be released and then
+    /// observe the stored value. If the initializer panics, then the lock is
+    /// released in an empty state.
+    pub fn get_or_spin_init(&self, f: impl FnOnce() -> T) -> MutexGuard<'_, T> {
+        let mut guard = self.spin_lock();
+        if guard.is_none() {
+            guard.replace(f());
+        }
+        MutexGuard::new(guard)
+    }
+
+    /// In a spin loop, wait to get an exclusive lock on the contained value
+    /// while the lock is held by another thread. If the lock is observed to be
+    /// empty and unlocked, then `None` is returned immediately rather than
+    /// waiting for the slot to be filled.
+    pub fn spin_get_if_present(&self) -> Option<MutexGuard<'_, T>> {
+        self.check_reentrant();
+        let backoff = Backoff::new();
+        loop {
+            match self.try_get() {
+                Ok(guard) => return Some(guard),
+                Err(OptionLockError::FillState) => return None,
+                Err(OptionLockError::Unavailable) => {
+                    self.spin_until_with(&backoff, |lock| !lock.is_locked())
+                }
+            }
+        }
+    }
+
+    /// In a spin loop, wait until the predicate returns `true` for the lock,
+    /// such as `OptionLock::is_some_unlocked`. This does not acquire the lock,
+    /// so the state may change again before the caller acts on it.
+    pub fn spin_until(&self, f: impl Fn(&Self) -> bool) {
+        self.spin_until_with(&Backoff::new(), f)
+    }
+
+    #[inline]
+    fn spin_until_with(&self, backoff: &Backoff, f: impl Fn(&Self) -> bool) {
+        while !f(self) {
+            backoff.spin();
+        }
+    }
+
+    #[cfg(feature = "std")]
+    /// Wait to get an exclusive lock on the contained value, spinning and then
+    /// yielding the current thread, until the timeout has elapsed. On timeout,
+    /// `Err(FillState)` is returned if the lock is empty and unlocked, and
+    /// otherwise `Err(Unavailable)`.
+    pub fn spin_get_timeout(&self, timeout: ::std::time::Duration) -> TryGetResult<'_, T> {
+        let deadline = ::std::time::Instant::now() + timeout;
+        let backoff = Backoff::new();
+        loop {
+            if let Ok(guard) = self.try_get() {
+                return Ok(guard);
+            }
+            if ::std::time::Instant::now() >= deadline {
+                return Err(if self.is_none_unlocked() {
+                    OptionLockError::FillState
+                } else {
+                    OptionLockError::Unavailable
+                });
+            }
+            if backoff.is_completed() {
+                ::std::thread::yield_now();
+            } else {
+                backoff.spin();
             }
         }
     }
 
+    /// In a spin loop, wait for an empty slot and store a value.
+    #[inline]
+    pub fn spin_fill(&self, value: T) {
+        self.spin_fill_with(|| value)
+    }
+
+    /// In a spin loop, wait for an empty slot and store the result of an
+    /// initializer function. The initializer is only called once the lock
+    /// has been acquired.
+    pub fn spin_fill_with(&self, f: impl FnOnce() -> T) {
+        self.spin_lock_none().replace(f());
+    }
+
     /// In a spin loop, wait to acquire the lock.
     pub fn spin_lock(&self) -> OptionGuard<'_, T> {
+        self.check_reentrant();
+        let backoff = Backoff::new();
         loop {
-            if let Ok(guard) = self.try_lock() {
+            if let Ok(guard) = self.try_lock_weak() {
                 return guard;
             }
-            while self.is_locked() {
-                spin_loop();
+            self.spin_until_with(&backoff, |lock| !lock.is_locked());
+        }
+    }
+
+    #[cfg(feature = "ticket")]
+    /// In a spin loop, wait to acquire the lock in order of arrival relative to
+    /// other callers of `spin_lock_fair`. Each caller takes a ticket and waits
+    /// for its turn before competing for the lock, so that only one of these
+    /// callers competes at a time. Other lock operations are not ordered, and
+    /// may still acquire the lock ahead of a waiting caller.
+    pub fn spin_lock_fair(&self) -> OptionGuard<'_, T> {
+        self.check_reentrant();
+        // the issued ticket wraps around without carrying into the lower half
+        let ticket = (self.tickets.fetch_add(1 << 16, Ordering::Relaxed) >> 16) as u16;
+        let backoff = Backoff::new();
+        while self.tickets.load(Ordering::Acquire) as u16 != ticket {
+            #[cfg(feature = "std")]
+            if backoff.is_completed() {
+                ::std::thread::yield_now();
+                continue;
             }
+            backoff.spin();
         }
+        let guard = self.spin_lock();
+        // advance the ticket being served, without carrying into the upper half
+        let _ = self
+            .tickets
+            .fetch_update(Ordering::Release, Ordering::Relaxed, |tickets| {
+                Some(tickets & !0xffff | (tickets as u16).wrapping_add(1) as u32)
+            });
+        guard
+    }
+
+    #[cfg(feature = "ticket")]
+    /// Get the number of callers of `spin_lock_fair` which are waiting for
+    /// their turn to acquire the lock.
+    pub fn fair_waiters(&self) -> usize {
+        let tickets = self.tickets.load(Ordering::Relaxed);
+        ((tickets >> 16) as u16).wrapping_sub(tickets as u16) as usize
     }
 
     /// In a spin loop, wait to acquire the lock with an empty slot.
     pub fn spin_lock_none(&self) -> OptionGuard<'_, T> {
+        let backoff = Backoff::new();
         loop {
             if let Ok(guard) = self.try_lock_none() {
                 return guard;
             }
-            while !self.is_none_unlocked() {
-                spin_loop();
-            }
+            self.spin_until_with(&backoff, Self::is_none_unlocked);
         }
     }
 
     /// In a spin loop, wait to take a value from the lock.
     pub fn spin_take(&self) -> T {
+        let backoff = Backoff::new();
+        loop {
+            if let Ok(result) = self.try_take() {
+                return result;
+            }
+            self.spin_until_with(&backoff, Self::is_some_unlocked);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    /// Wait for an empty slot and store a value, yielding the current thread
+    /// while the lock is held or filled.
+    pub fn fill_yield(&self, value: T) {
+        let mut guard = loop {
+            if let Ok(guard) = self.try_lock_none() {
+                break guard;
+            }
+            while !self.is_none_unlocked() {
+                ::std::thread::yield_now();
+            }
+        };
+        guard.replace(value);
+    }
+
+    #[cfg(feature = "std")]
+    /// Wait to take a value from the lock, yielding the current thread
+    /// while the lock is held or empty.
+    pub fn take_yield(&self) -> T {
         loop {
             if let Ok(result) = self.try_take() {
                 return result;
             }
             while !self.is_some_unlocked() {
-                spin_loop();
+                ::std::thread::yield_now();
             }
         }
     }
@@ -203,17 +889,77 @@ impl<T> OptionLock<T> {
     ///
     /// On successful acquisition a `MutexGuard<'_, T>` is returned, representing
     /// an exclusive read/write lock.
-    pub fn try_get(&self) -> Result<MutexGuard<'_, T>, OptionLockError> {
+    #[inline]
+    pub fn try_get(&self) -> TryGetResult<'_, T> {
         match self.state.compare_exchange(
             State::AVAILABLE,
             State::SOME,
             Ordering::AcqRel,
             Ordering::Relaxed,
         ) {
-            Ok(_) => Ok(MutexGuard::new(OptionGuard::new(self, true))),
-            Err(State::FREE) => Err(OptionLockError::FillState),
-            Err(_) => Err(OptionLockError::Unavailable),
+            Ok(_) => {
+                self.observe(State::AVAILABLE, State::SOME);
+                Ok(MutexGuard::new(OptionGuard::new(self, true)))
+            }
+            Err(state) => Err(fill_state_error(state, State::FREE)),
+        }
+    }
+
+    /// Try to acquire an exclusive lock around the contained value, first
+    /// storing the provided value if the lock is empty.
+    pub fn try_get_or_insert(&self, default: T) -> TryGetResult<'_, T> {
+        let mut guard = self.try_lock()?;
+        if guard.is_none() {
+            guard.replace(default);
+        }
+        Ok(MutexGuard::new(guard))
+    }
+
+    /// Try to acquire an exclusive lock around the contained value, first
+    /// storing the default value if the lock is empty.
+    pub fn try_get_or_default(&self) -> TryGetResult<'_, T>
+    where
+        T: Default,
+    {
+        let mut guard = self.try_lock()?;
+        if guard.is_none() {
+            guard.replace(T::default());
+        }
+        Ok(MutexGuard::new(guard))
+    }
+
+    /// Try to acquire an exclusive lock around the contained value, first
+    /// storing the result of the initializer function if the lock is empty.
+    /// If the initializer fails then its error is returned and the lock is
+    /// left empty.
+    pub fn try_get_or_try_init<E>(
+        &self,
+        f: impl FnOnce() -> Result<T, E>,
+    ) -> Result<TryGetResult<'_, T>, E> {
+        let mut guard = match self.try_lock() {
+            Ok(guard) => guard,
+            Err(err) => return Ok(Err(err)),
+        };
+        if guard.is_none() {
+            guard.replace(f()?);
         }
+        Ok(Ok(MutexGuard::new(guard)))
+    }
+
+    /// Try to acquire an exclusive lock around a contained value, providing
+    /// pinned access to the value.
+    ///
+    /// # Safety
+    /// As for `Pin::new_unchecked`, once a guard has been returned the value
+    /// must stay in place until it is dropped, even after the guard is
+    /// released. It must not be taken or replaced through another guard, and
+    /// the lock must not be consumed or moved while it holds the value. For
+    /// an `Unpin` value, `try_get` may be used instead with `Pin::new`.
+    #[inline]
+    pub unsafe fn try_get_pinned(&self) -> Result<PinnedMutexGuard<'_, T>, OptionLockError> {
+        // SAFETY: upheld by the caller
+        self.try_get()
+            .map(|guard| unsafe { PinnedMutexGuard::new(guard) })
     }
 
     #[cfg(feature = "alloc")]
@@ -223,16 +969,43 @@ impl<T> OptionLock<T> {
     /// an exclusive read/write lock.
     pub fn try_get_arc(self: &Arc<Self>) -> Result<MutexGuardArc<T>, OptionLockError> {
         self.try_get()
-            .map(|guard| MutexGuardArc::new(unsafe { transmute(self.clone()) }, guard))
+            .map(|guard| MutexGuardArc::new(Mutex::from_arc(self.clone()), guard))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Try to acquire an exclusive lock around the value in an `Rc<OptionLock>`.
+    ///
+    /// On successful acquisition a `MutexGuardRc<T>` is returned, representing
+    /// an exclusive read/write lock.
+    pub fn try_get_rc(self: &Rc<Self>) -> Result<MutexGuardRc<T>, OptionLockError> {
+        self.try_get()
+            .map(|guard| MutexGuardRc::new(Mutex::from_rc(self.clone()), guard))
+    }
+
+    /// Try to acquire an exclusive lock around a contained value and run a
+    /// function with the resulting guard, returning its result.
+    ///
+    /// The lock is released when the function returns, or if it panics. As for
+    /// any `MutexGuard`, when the `std` feature is enabled a panic within the
+    /// function drops the value and leaves the lock empty, so that a `Mutex`
+    /// is poisoned. This is disabled by the `no_poison` feature.
+    pub fn get_scope<R>(
+        &self,
+        f: impl FnOnce(&mut MutexGuard<'_, T>) -> R,
+    ) -> Result<R, OptionLockError> {
+        let mut guard = self.try_get()?;
+        Ok(f(&mut guard))
     }
 
     /// Try to store a value, if the slot is currently empty and a lock can be acquired.
+    #[inline]
     pub fn try_fill(&self, value: T) -> Result<(), T> {
         match self
             .state
             .compare_exchange(State::FREE, 0, Ordering::AcqRel, Ordering::Relaxed)
         {
             Ok(_) => {
+                self.observe(State::FREE, 0);
                 OptionGuard::new(self, false).replace(value);
                 Ok(())
             }
@@ -248,21 +1021,197 @@ impl<T> OptionLock<T> {
         Ok(())
     }
 
+    /// Store the result of an initializer function if the slot is currently empty
+    /// and a lock can be acquired, returning a guard over the stored value. If a
+    /// lock cannot be acquired, then the initializer is never called.
+    ///
+    /// A guard is returned rather than a plain reference, as the value could
+    /// otherwise be taken by another thread as soon as the lock is released.
+    pub fn try_fill_with_ref(&self, f: impl FnOnce() -> T) -> TryGetResult<'_, T> {
+        let mut guard = self.try_lock_none()?;
+        guard.replace(f());
+        Ok(MutexGuard::new(guard))
+    }
+
+    /// Try to fill an empty slot with the result of an initializer function,
+    /// reporting whether the current caller won the race to initialize it.
+    /// The initializer is only called if the slot is empty and the lock is
+    /// acquired, in which case a guard over the new value is returned.
+    pub fn try_init_slot(&self, f: impl FnOnce() -> T) -> InitOutcome<'_, T> {
+        match self.try_fill_with_ref(f) {
+            Ok(guard) => InitOutcome::Won(guard),
+            Err(OptionLockError::FillState) => InitOutcome::AlreadyFilled,
+            Err(OptionLockError::Unavailable) => InitOutcome::Contended,
+        }
+    }
+
     /// Try to acquire an exclusive lock.
     ///
     /// On successful acquisition an `OptionGuard<'_, T>` is returned, representing
     /// an exclusive read/write lock.
-    pub fn try_lock(&self) -> Result<OptionGuard<'_, T>, OptionLockError> {
-        let state = self.state.fetch_and(!State::FREE, Ordering::Release);
-        if state & State::FREE != 0 {
+    #[inline]
+    pub fn try_lock(&self) -> TryLockResult<'_, T> {
+        // SAFETY: the lock is released when the guard is dropped
+        match unsafe { self.raw_try_acquire() } {
+            Some(is_some) => Ok(OptionGuard::new(self, is_some)),
+            None => Err(unavailable_error()),
+        }
+    }
+
+    /// Try to acquire an exclusive lock using a weak compare-and-exchange
+    /// operation. This may fail spuriously even when the lock is available,
+    /// but can be cheaper on some platforms when it is called in a retry loop.
+    #[inline]
+    pub fn try_lock_weak(&self) -> TryLockResult<'_, T> {
+        let state = self.state.value();
+        if state & State::FREE != 0
+            && self
+                .state
+                .compare_exchange_weak(
+                    state,
+                    state & !State::FREE,
+                    Ordering::AcqRel,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            self.observe(state, state & !State::FREE);
             Ok(OptionGuard::new(self, state & State::SOME != 0))
         } else {
-            Err(OptionLockError::Unavailable)
+            Err(unavailable_error())
         }
     }
 
-    #[cfg(feature = "alloc")]
-    /// Try to acquire an exclusive lock from a reference to an `Arc<OptionLock>`.
+    /// Try to acquire an exclusive lock without creating a guard, returning
+    /// `Some(is_some)` on success to indicate whether the lock contains a value.
+    /// This is the primitive operation underlying `try_lock` and its guard.
+    ///
+    /// While the lock is held, the value may be accessed using `get_unchecked`
+    /// and `get_mut_unchecked`.
+    ///
+    /// # Safety
+    /// On success the lock must be released by a subsequent call to
+    /// `raw_release`, otherwise it will remain locked.
+    #[inline]
+    pub unsafe fn raw_try_acquire(&self) -> Option<bool> {
+        let state = self.state.fetch_and(!State::FREE, Ordering::AcqRel);
+        if state & State::FREE != 0 {
+            self.observe(state, state & !State::FREE);
+            Some(state & State::SOME != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Release a lock previously acquired using `raw_try_acquire`, recording
+    /// whether it contains a value. This is the operation performed when an
+    /// `OptionGuard` is dropped.
+    ///
+    /// # Safety
+    /// The lock must be held by the caller, and `is_some` must accurately
+    /// reflect whether the lock contains an initialized value.
+    #[inline]
+    pub unsafe fn raw_release(&self, is_some: bool) {
+        let state = if is_some {
+            State::AVAILABLE
+        } else {
+            State::FREE
+        };
+        self.state.store(state, Ordering::Release);
+        self.observe(state & !State::FREE, state);
+    }
+
+    /// Access the atomic state of the lock directly, for example to combine a
+    /// `compare_exchange` with operations on adjacent atomics. The state is
+    /// composed of the flags defined in the [`state`](crate::state) module.
+    ///
+    /// # Safety
+    /// Any modification of the state must correspond to a valid transition:
+    /// the lock may be acquired by clearing the `FREE` bit when it is set, and
+    /// must then be released as if by `raw_release`. The `SOME` bit must never
+    /// be changed except by the holder of the lock, and must accurately reflect
+    /// whether the lock contains an initialized value. A lock acquired in this
+    /// way is not tracked by the `debug_reentrancy` or `ticket` features.
+    #[inline]
+    pub unsafe fn raw_state(&self) -> &AtomicU8 {
+        &self.state
+    }
+
+    /// Try to acquire an exclusive lock, returning the guard along with a flag
+    /// indicating whether the lock contains a value.
+    #[inline]
+    pub fn try_lock_state(&self) -> Result<(OptionGuard<'_, T>, bool), OptionLockError> {
+        self.try_lock().map(|guard| {
+            let is_some = guard.is_some();
+            (guard, is_some)
+        })
+    }
+
+    /// Try to acquire an exclusive lock and run a function with the resulting
+    /// guard, returning its result.
+    ///
+    /// The lock is released when the function returns, or if it panics.
+    pub fn scope<R>(
+        &self,
+        f: impl FnOnce(&mut OptionGuard<'_, T>) -> R,
+    ) -> Result<R, OptionLockError> {
+        let mut guard = self.try_lock()?;
+        Ok(f(&mut guard))
+    }
+
+    /// Try to acquire an exclusive lock and run a function with the resulting
+    /// guard, returning the `ControlFlow` it produces. This is intended for use
+    /// within a retry loop driven by the caller.
+    ///
+    /// The lock is released before returning, whether the function breaks or
+    /// continues.
+    #[inline]
+    pub fn visit<B>(
+        &self,
+        f: impl FnOnce(&mut OptionGuard<'_, T>) -> ControlFlow<B>,
+    ) -> Result<ControlFlow<B>, OptionLockError> {
+        self.scope(f)
+    }
+
+    /// Try to acquire an exclusive lock up to a maximum number of attempts,
+    /// with a randomized exponential backoff between attempts.
+    ///
+    /// The random number generator is seeded per call, mixing a global
+    /// counter with the address of the calling stack frame, so that threads
+    /// contending for the same lock do not back off in lockstep.
+    #[cfg(feature = "jitter")]
+    pub fn try_lock_backoff(&self, attempts: usize) -> TryLockResult<'_, T> {
+        static SEED: AtomicU32 = AtomicU32::new(0);
+        let frame = 0u8;
+        let seed = SEED.fetch_add(0x9e37_79b9, Ordering::Relaxed)
+            ^ (&frame as *const u8 as usize as u32).rotate_left(16);
+        let mut rng = XorShiftRng::new(seed);
+        self.try_lock_backoff_with(attempts, &mut rng)
+    }
+
+    /// Try to acquire an exclusive lock up to a maximum number of attempts,
+    /// with a randomized exponential backoff between attempts using the
+    /// provided random number generator.
+    #[cfg(feature = "jitter")]
+    pub fn try_lock_backoff_with(
+        &self,
+        attempts: usize,
+        rng: &mut impl BackoffRng,
+    ) -> TryLockResult<'_, T> {
+        let backoff = Backoff::new();
+        let mut remain = attempts;
+        loop {
+            let result = self.try_lock();
+            remain = remain.saturating_sub(1);
+            if result.is_ok() || remain == 0 {
+                return result;
+            }
+            backoff.spin_jitter(rng);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Try to acquire an exclusive lock from a reference to an `Arc<OptionLock>`.
     ///
     /// On successful acquisition an `OptionGuardArc<T>` is returned, representing
     /// an exclusive read/write lock.
@@ -271,18 +1220,41 @@ impl<T> OptionLock<T> {
             .map(|guard| OptionGuardArc::new(self.clone(), guard))
     }
 
+    #[cfg(feature = "alloc")]
+    /// Try to acquire an exclusive lock from a reference to an `Rc<OptionLock>`.
+    ///
+    /// On successful acquisition an `OptionGuardRc<T>` is returned, representing
+    /// an exclusive read/write lock.
+    pub fn try_lock_rc(self: &Rc<Self>) -> Result<OptionGuardRc<T>, OptionLockError> {
+        self.try_lock()
+            .map(|guard| OptionGuardRc::new(self.clone(), guard))
+    }
+
     /// Try to acquire an exclusive lock when there is no value currently stored.
-    pub fn try_lock_none(&self) -> Result<OptionGuard<'_, T>, OptionLockError> {
+    #[inline]
+    pub fn try_lock_none(&self) -> TryLockResult<'_, T> {
         match self
             .state
             .compare_exchange(State::FREE, 0, Ordering::AcqRel, Ordering::Relaxed)
         {
-            Ok(_) => return Ok(OptionGuard::new(self, false)),
-            Err(State::AVAILABLE) => Err(OptionLockError::FillState),
-            Err(_) => Err(OptionLockError::Unavailable),
+            Ok(_) => {
+                self.observe(State::FREE, 0);
+                Ok(OptionGuard::new(self, false))
+            }
+            Err(state) => Err(fill_state_error(state, State::AVAILABLE)),
         }
     }
 
+    /// Try to reserve an empty slot, returning a guard which may be filled
+    /// using `FillGuard::commit` or released using `FillGuard::cancel`.
+    #[inline]
+    pub fn try_reserve(&self) -> Result<FillGuard<'_, T>, OptionLockError> {
+        self.try_lock_none().map(|guard| FillGuard {
+            guard: Some(guard),
+            strict: false,
+        })
+    }
+
     #[cfg(feature = "alloc")]
     /// Try to acquire an exclusive lock when there is no value currently stored.
     pub fn try_lock_empty_arc(self: &Arc<Self>) -> Result<OptionGuardArc<T>, OptionLockError> {
@@ -290,29 +1262,341 @@ impl<T> OptionLock<T> {
             .map(|guard| OptionGuardArc::new(self.clone(), guard))
     }
 
+    #[cfg(feature = "alloc")]
+    /// Try to acquire an exclusive lock when there is no value currently stored.
+    pub fn try_lock_empty_rc(self: &Rc<Self>) -> Result<OptionGuardRc<T>, OptionLockError> {
+        self.try_lock_none()
+            .map(|guard| OptionGuardRc::new(self.clone(), guard))
+    }
+
+    /// Try to acquire an exclusive lock and modify the contained value in place.
+    ///
+    /// If a value is present then the function is called with a mutable reference
+    /// to it and its result is returned as `Ok(Some(result))`. If the lock is empty
+    /// then the function is not called and `Ok(None)` is returned.
+    pub fn try_modify<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<Option<R>, OptionLockError> {
+        let mut guard = self.try_lock()?;
+        Ok(guard.as_mut().map(f))
+    }
+
+    /// Try to acquire an exclusive lock and pass ownership of the stored value
+    /// (if any) to a function. The first element of its result is stored in the
+    /// lock, and the second is returned once the lock is released.
+    ///
+    /// If the function panics then the lock is left empty.
+    pub fn try_update_take<R>(
+        &self,
+        f: impl FnOnce(Option<T>) -> (Option<T>, R),
+    ) -> Result<R, OptionLockError> {
+        let mut guard = self.try_lock()?;
+        let (value, result) = f(guard.take());
+        if let Some(value) = value {
+            guard.replace(value);
+        }
+        Ok(result)
+    }
+
+    /// Try to acquire an exclusive lock and apply a function to the contained
+    /// value, returning its result after the lock is released.
+    ///
+    /// If the lock is empty then the function is not called and
+    /// `Err(FillState)` is returned.
+    pub fn try_get_map<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, OptionLockError> {
+        let guard = self.try_get()?;
+        Ok(f(&guard))
+    }
+
+    /// Try to lease the stored value to a function, returning it to the lock
+    /// afterwards. The lock is held while the function runs, so other threads
+    /// can neither take the value nor fill the slot in the meantime.
+    ///
+    /// The value remains in the lock even if the function panics. If the lock
+    /// is empty then the function is not called and `Err(FillState)` is
+    /// returned.
+    pub fn lease<R>(&self, f: impl FnOnce(&mut T) -> R) -> Result<R, OptionLockError> {
+        let mut guard = self.try_lock()?;
+        match guard.as_mut() {
+            Some(value) => Ok(f(value)),
+            None => Err(OptionLockError::FillState),
+        }
+    }
+
+    /// Try to acquire an exclusive lock and store a value, returning the previous
+    /// value (if any) along with a guard over the new value.
+    ///
+    /// If the lock cannot be acquired then the value is returned with the error.
+    pub fn try_replace_keep(
+        &self,
+        value: T,
+    ) -> Result<(Option<T>, MutexGuard<'_, T>), (T, OptionLockError)> {
+        match self.try_lock() {
+            Ok(mut guard) => {
+                let prev = guard.replace(value);
+                Ok((prev, MutexGuard::new(guard)))
+            }
+            Err(err) => Err((value, err)),
+        }
+    }
+
+    /// Try to exchange the stored value for another within a single lock
+    /// acquisition, returning the previous value (if any). The lock is left
+    /// filled.
+    ///
+    /// If the lock cannot be acquired then the value is returned as an error.
+    #[inline]
+    pub fn try_exchange(&self, value: T) -> Result<Option<T>, T> {
+        match self.try_lock() {
+            Ok(mut guard) => Ok(guard.replace(value)),
+            Err(_) => Err(value),
+        }
+    }
+
+    /// Try to acquire an exclusive lock and take the stored value, if any,
+    /// leaving the lock empty. Unlike `try_take`, an empty lock results in
+    /// `Ok(None)` rather than an error.
+    #[inline]
+    pub fn try_empty(&self) -> Result<Option<T>, OptionLockError> {
+        self.try_lock().map(OptionGuard::into_inner)
+    }
+
+    /// Split the lock into producer and consumer handles. The `Filler` may only
+    /// store values in the lock, while the `Taker` may only remove them.
+    #[inline]
+    pub fn split(&self) -> (Filler<'_, T>, Taker<'_, T>) {
+        (Filler::new(self), Taker::new(self))
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Split an `Arc<OptionLock>` into owned producer and consumer handles.
+    #[inline]
+    pub fn split_arc(self: &Arc<Self>) -> (FillerArc<T>, TakerArc<T>) {
+        (FillerArc::new(self.clone()), TakerArc::new(self.clone()))
+    }
+
     /// Try to take a stored value from the lock.
+    ///
+    /// If the lock is empty then `Err(FillState)` is returned, and if it is
+    /// held by another thread then `Err(Unavailable)`.
     #[inline]
     pub fn try_take(&self) -> Result<T, OptionLockError> {
         self.try_get().map(MutexGuard::extract)
     }
 
+    /// Try to take a stored value from the lock, returning `Ok(None)` if the
+    /// lock is empty. An error is only returned if the lock is currently held.
+    /// This is equivalent to `try_empty`, and is the counterpart of
+    /// `try_clone_opt`.
+    #[inline]
+    pub fn try_take_opt(&self) -> Result<Option<T>, OptionLockError> {
+        self.try_empty()
+    }
+
+    #[cfg(feature = "async")]
+    /// Poll to take a stored value from the lock.
+    ///
+    /// This busy-polls: the lock does not store wakers, so when the value is
+    /// not available the task is woken immediately and will be polled again,
+    /// using CPU time until the slot is filled. It is only appropriate when
+    /// the slot is expected to be filled shortly. A `WakeSlot` registers the
+    /// waker instead.
+    pub fn poll_take(&self, cx: &mut Context<'_>) -> Poll<T> {
+        match self.try_take() {
+            Ok(value) => Poll::Ready(value),
+            Err(_) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    /// Poll to store a value in an empty slot. On success the value is
+    /// taken from `value`, otherwise it is left intact for the next attempt.
+    ///
+    /// This busy-polls: the lock does not store wakers, so the waker is not
+    /// registered. When the slot is not available the task is woken
+    /// immediately and will be polled again, using CPU time until the slot is
+    /// emptied. It is only appropriate when the slot is expected to be
+    /// emptied shortly.
+    ///
+    /// Panics if `value` is `None`.
+    pub fn poll_fill(&self, cx: &mut Context<'_>, value: &mut Option<T>) -> Poll<()> {
+        let fill = value.take().expect("poll_fill called without a value");
+        match self.try_fill(fill) {
+            Ok(()) => Poll::Ready(()),
+            Err(fill) => {
+                value.replace(fill);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Try to move a value from one lock to another. Both locks are acquired
+    /// in order of their addresses, so that concurrent transfers in opposite
+    /// directions do not repeatedly block each other.
+    ///
+    /// Returns `Ok(true)` if the value was moved, or `Ok(false)` if `src` was
+    /// empty, `dst` was already filled, or both refer to the same lock. If
+    /// either lock is held then `Err(Unavailable)` is returned.
+    pub fn transfer(src: &Self, dst: &Self) -> Result<bool, OptionLockError> {
+        if src.ptr_eq(dst) {
+            return Ok(false);
+        }
+        let (mut src_guard, mut dst_guard) = if (src as *const Self) < (dst as *const Self) {
+            let src_guard = src.try_lock()?;
+            (src_guard, dst.try_lock()?)
+        } else {
+            let dst_guard = dst.try_lock()?;
+            (src.try_lock()?, dst_guard)
+        };
+        if dst_guard.is_none() {
+            if let Some(value) = src_guard.take() {
+                dst_guard.replace(value);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Try to acquire exclusive locks around the values of two locks. The locks
+    /// are acquired in order of their addresses, so that threads locking the
+    /// same pair in the opposite order do not repeatedly block each other. If
+    /// either lock cannot be acquired then neither is held.
+    ///
+    /// If both arguments refer to the same lock then `Err(Unavailable)` is
+    /// returned.
+    pub fn try_get_both<'a, U>(
+        a: &'a Self,
+        b: &'a OptionLock<U>,
+    ) -> Result<(MutexGuard<'a, T>, MutexGuard<'a, U>), OptionLockError> {
+        if (a as *const Self as usize) < (b as *const OptionLock<U> as usize) {
+            let a_guard = a.try_get()?;
+            Ok((a_guard, b.try_get()?))
+        } else {
+            let b_guard = b.try_get()?;
+            Ok((a.try_get()?, b_guard))
+        }
+    }
+
+    /// Store indexed values into the corresponding slots of a slice of locks,
+    /// returning the number of values stored. If a slot is already filled or
+    /// currently locked, then its index and value are returned as an error and
+    /// no further values are stored.
+    ///
+    /// Panics if an index is out of bounds for the slice.
+    pub fn fill_from_indexed(
+        slots: &[Self],
+        items: impl IntoIterator<Item = (usize, T)>,
+    ) -> Result<usize, (usize, T)> {
+        let mut count = 0;
+        for (index, value) in items {
+            slots[index]
+                .try_fill(value)
+                .map_err(|value| (index, value))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Create an iterator which takes the stored value each time it is advanced,
+    /// ending when the lock is empty or held by another guard. The iterator
+    /// may be advanced again after a value is stored.
+    ///
+    /// When the iterator is dropped, any value stored in the meantime is
+    /// dropped so that the lock is left empty and unlocked. This does not wait
+    /// for a guard held elsewhere: if the lock is held at that point, then it
+    /// is left untouched. The value is dropped after the lock is released, so
+    /// the lock is left empty even if its destructor panics.
+    #[inline]
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain { lock: self }
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Take the stored values from every lock in a slice, only if each of them
+    /// is filled and can be acquired. Otherwise an error is returned and the
+    /// contents of the slots are left untouched. The locks are acquired in
+    /// the order of the slice.
+    pub fn try_take_all(slots: &[Self]) -> Result<Vec<T>, OptionLockError> {
+        let guards = slots
+            .iter()
+            .map(Self::try_get)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(guards.into_iter().map(MutexGuard::extract).collect())
+    }
+
+    /// Take the stored values from a slice of locks, skipping any slots which
+    /// are empty or currently locked. The values are taken lazily as the
+    /// iterator is advanced.
+    pub fn drain_slice(slots: &[Self]) -> impl Iterator<Item = T> + '_ {
+        slots.iter().filter_map(|slot| slot.try_take().ok())
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Take the stored values from a slice of locks, skipping any slots which
+    /// are empty. Each of the locks is acquired before any value is taken, so
+    /// if a slot is currently locked then `Err(Unavailable)` is returned and
+    /// the contents of the slots are left untouched.
+    ///
+    /// ```
+    /// use option_lock::{OptionLock, OptionLockError};
+    ///
+    /// let slots = [OptionLock::new(1), OptionLock::empty(), OptionLock::new(3)];
+    /// let guard = slots[2].try_lock().unwrap();
+    /// assert_eq!(
+    ///     OptionLock::try_drain_slice(&slots),
+    ///     Err(OptionLockError::Unavailable)
+    /// );
+    /// drop(guard);
+    /// assert_eq!(OptionLock::try_drain_slice(&slots), Ok(vec![1, 3]));
+    /// ```
+    pub fn try_drain_slice(slots: &[Self]) -> Result<Vec<T>, OptionLockError> {
+        let guards = slots
+            .iter()
+            .map(Self::try_lock)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(guards
+            .into_iter()
+            .filter_map(OptionGuard::into_inner)
+            .collect())
+    }
+
     /// Replace the value in an owned `OptionLock`.
     pub fn replace(&mut self, value: T) -> Option<T> {
-        let result = if self.is_some() {
-            Some(unsafe { self.as_mut_ptr().read() })
+        let result = if self.state.is_some_mut() {
+            // SAFETY: the lock is exclusively borrowed and contains a value,
+            // which is immediately replaced
+            Some(unsafe { self.read_value() })
         } else {
-            self.state.fetch_or(State::SOME, Ordering::Relaxed);
+            *self.state.0.get_mut() |= State::SOME;
             None
         };
-        unsafe { self.as_mut_ptr().write(value) };
+        // SAFETY: the lock is exclusively borrowed and the slot is now empty
+        unsafe { self.write_value(value) };
         result
     }
 
+    /// Store a value in an owned lock instance, dropping the previous value
+    /// (if any) in place rather than returning it.
+    pub fn set_dropping(&mut self, value: T) {
+        if self.state.is_some_mut() {
+            // SAFETY: the lock is exclusively borrowed and contains a value
+            *unsafe { self.value_mut() } = value;
+        } else {
+            // SAFETY: the lock is exclusively borrowed and the slot is empty
+            unsafe { self.write_value(value) };
+            *self.state.0.get_mut() |= State::SOME;
+        }
+    }
+
     /// Take the value (if any) from an owned `OptionLock`.
     pub fn take(&mut self) -> Option<T> {
-        if self.is_some() {
-            self.state.fetch_and(!State::SOME, Ordering::Relaxed);
-            Some(unsafe { self.as_mut_ptr().read() })
+        if self.state.is_some_mut() {
+            // SAFETY: the lock is exclusively borrowed and contains a value
+            let value = unsafe { self.read_value() };
+            *self.state.0.get_mut() &= !State::SOME;
+            Some(value)
         } else {
             None
         }
@@ -320,11 +1604,52 @@ impl<T> OptionLock<T> {
 }
 
 impl<T: Clone> OptionLock<T> {
-    /// Try to clone the contained resource.
+    /// Try to clone the contained resource. For a shared pointer type such as
+    /// `Arc<T>`, this only increments the reference count.
+    ///
+    /// If the lock is empty then `Err(FillState)` is returned, and if it is
+    /// held by another thread then `Err(Unavailable)`. Use `try_clone_opt` to
+    /// treat an empty lock as a successful result.
     #[inline]
     pub fn try_clone(&self) -> Result<T, OptionLockError> {
         self.try_get().map(|g| (*g).clone())
     }
+
+    /// Try to clone the contained resource, returning `Ok(None)` if the lock
+    /// is empty. An error is only returned if the lock is currently held.
+    #[inline]
+    pub fn try_clone_opt(&self) -> Result<Option<T>, OptionLockError> {
+        match self.try_clone() {
+            Ok(value) => Ok(Some(value)),
+            Err(OptionLockError::FillState) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Try to clone the contained resource into `out`, reusing the existing
+    /// value through `Clone::clone_from` where possible. Returns `false` if the
+    /// lock is empty or currently held, in which case `out` is unchanged.
+    #[inline]
+    pub fn try_clone_into(&self, out: &mut Option<T>) -> bool {
+        match self.try_get() {
+            Ok(guard) => {
+                match out {
+                    Some(prev) => prev.clone_from(&*guard),
+                    None => *out = Some((*guard).clone()),
+                }
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Clone the contained value for persistence, without waiting. If the lock
+    /// is empty or currently held then `None` is returned, so the result may not
+    /// reflect a concurrent update. Use `restore` to reconstruct the lock.
+    #[inline]
+    pub fn snapshot(&self) -> Option<T> {
+        self.try_clone().ok()
+    }
 }
 
 impl<T: Copy> OptionLock<T> {
@@ -338,9 +1663,8 @@ impl<T: Copy> OptionLock<T> {
 impl<T> Drop for OptionLock<T> {
     fn drop(&mut self) {
         if self.state.is_some_mut() {
-            unsafe {
-                drop_in_place(self.as_mut_ptr());
-            }
+            // SAFETY: the lock is being dropped and contains a value
+            unsafe { self.drop_value() };
         }
     }
 }
@@ -361,9 +1685,9 @@ impl<T> From<Option<T>> for OptionLock<T> {
     }
 }
 
-impl<T> Into<Option<T>> for OptionLock<T> {
-    fn into(mut self) -> Option<T> {
-        self.take()
+impl<T> From<OptionLock<T>> for Option<T> {
+    fn from(mut lock: OptionLock<T>) -> Self {
+        lock.take()
     }
 }
 
@@ -373,16 +1697,57 @@ impl<T> Debug for OptionLock<T> {
     }
 }
 
+struct DebugValue<'a, T>(&'a OptionLock<T>);
+
+impl<T: Debug> Debug for DebugValue<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0.try_get() {
+            Ok(guard) => f.debug_tuple("OptionLock").field(&Some(&*guard)).finish(),
+            Err(OptionLockError::FillState) => f.write_str("OptionLock(None)"),
+            Err(OptionLockError::Unavailable) => f.write_str("OptionLock(Locked)"),
+        }
+    }
+}
+
 /// An exclusive guard for the value of an [`OptionLock`]
+///
+/// With the `debug_reentrancy` feature in debug builds, the guard is tied to
+/// the thread which acquired the lock and cannot be sent to another thread.
 pub struct OptionGuard<'a, T> {
     lock: &'a OptionLock<T>,
     is_some: bool,
+    #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+    _owner: PhantomData<*const ()>,
 }
 
 impl<'a, T> OptionGuard<'a, T> {
     #[inline]
     pub(crate) fn new(lock: &'a OptionLock<T>, is_some: bool) -> Self {
-        Self { lock, is_some }
+        lock.owner_acquire();
+        // SAFETY: the guard is only created when the lock is acquired
+        unsafe { lock.hold_start() };
+        Self {
+            lock,
+            is_some,
+            #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+            _owner: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn lock(&self) -> &'a OptionLock<T> {
+        self.lock
+    }
+
+    /// Convert this guard into a `MutexGuard` if the lock contains a value,
+    /// without releasing the lock. Otherwise the guard is returned as an error.
+    #[inline]
+    pub fn into_mutex_guard(self) -> Result<MutexGuard<'a, T>, Self> {
+        if self.is_some {
+            Ok(MutexGuard::new(self))
+        } else {
+            Err(self)
+        }
     }
 }
 
@@ -390,7 +1755,8 @@ impl<T> OptionGuard<'_, T> {
     /// Obtain a shared reference to the contained value, if any.
     pub fn as_ref(&self) -> Option<&T> {
         if self.is_some {
-            Some(unsafe { &*self.lock.as_ptr() })
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(unsafe { self.lock.value_ref() })
         } else {
             None
         }
@@ -399,7 +1765,8 @@ impl<T> OptionGuard<'_, T> {
     /// Obtain an exclusive reference to the contained value, if any.
     pub fn as_mut(&mut self) -> Option<&mut T> {
         if self.is_some {
-            Some(unsafe { &mut *self.lock.as_mut_ptr() })
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(unsafe { self.lock.value_mut() })
         } else {
             None
         }
@@ -420,25 +1787,90 @@ impl<T> OptionGuard<'_, T> {
     /// Replace the value in the lock, returning the previous value, if any.
     pub fn replace(&mut self, value: T) -> Option<T> {
         if self.is_some {
-            Some(mem::replace(unsafe { &mut *self.lock.as_mut_ptr() }, value))
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(mem::replace(unsafe { self.lock.value_mut() }, value))
         } else {
+            // SAFETY: the guard holds the lock and the slot is empty
+            unsafe { self.lock.write_value(value) };
             self.is_some = true;
-            unsafe {
-                self.lock.as_mut_ptr().write(value);
-            }
             None
         }
     }
 
+    /// Store a value in the lock, dropping the previous value (if any) in
+    /// place rather than returning it.
+    pub fn set(&mut self, value: T) {
+        if self.is_some {
+            // SAFETY: the guard holds the lock and the slot contains a value
+            *unsafe { self.lock.value_mut() } = value;
+        } else {
+            // SAFETY: the guard holds the lock and the slot is empty
+            unsafe { self.lock.write_value(value) };
+            self.is_some = true;
+        }
+    }
+
+    /// Replace the value in the lock if the predicate returns `true` for the
+    /// current value, returning the previous value, if any. Otherwise the
+    /// provided value is returned as an error.
+    pub fn replace_if(
+        &mut self,
+        value: T,
+        pred: impl FnOnce(Option<&T>) -> bool,
+    ) -> Result<Option<T>, T> {
+        if pred(self.as_ref()) {
+            Ok(self.replace(value))
+        } else {
+            Err(value)
+        }
+    }
+
     /// Take the current value from the lock, if any.
     pub fn take(&mut self) -> Option<T> {
         if self.is_some {
             self.is_some = false;
-            Some(unsafe { self.lock.as_ptr().read() })
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(unsafe { self.lock.read_value() })
         } else {
             None
         }
     }
+
+    /// Take the current value from the lock, if any, and release the lock
+    /// leaving it empty.
+    #[inline]
+    pub fn into_inner(mut self) -> Option<T> {
+        self.take()
+    }
+
+    /// Take the current value from the lock, if any, leaving it empty.
+    #[inline]
+    pub fn clear(&mut self) -> Option<T> {
+        self.take()
+    }
+
+    /// Drop the current value in place, if any, leaving the lock empty.
+    pub fn drop_value(&mut self) {
+        if self.is_some {
+            // the flag is cleared first in case the destructor panics
+            self.is_some = false;
+            // SAFETY: the guard holds the lock and the slot contains a value
+            unsafe { self.lock.drop_value() };
+        }
+    }
+
+    /// Release the lock and spin to reacquire it, returning a new guard.
+    ///
+    /// The lock is not fair, so this only offers other threads an opportunity
+    /// to acquire the lock: a thread which is currently spinning may succeed
+    /// first, but the current thread may also reacquire the lock immediately.
+    /// The fill state of the lock may have changed by the time it is returned.
+    pub fn spin_relock(self) -> Self {
+        let lock = self.lock;
+        drop(self);
+        spin_loop();
+        lock.spin_lock()
+    }
 }
 
 impl<T: Debug> Debug for OptionGuard<'_, T> {
@@ -447,18 +1879,174 @@ impl<T: Debug> Debug for OptionGuard<'_, T> {
     }
 }
 
+impl<T: PartialEq> PartialEq<Option<T>> for OptionGuard<'_, T> {
+    fn eq(&self, other: &Option<T>) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
+impl<'b, T: PartialEq> PartialEq<OptionGuard<'b, T>> for OptionGuard<'_, T> {
+    fn eq(&self, other: &OptionGuard<'b, T>) -> bool {
+        self.as_ref() == other.as_ref()
+    }
+}
+
 impl<'a, T> Drop for OptionGuard<'a, T> {
+    #[inline]
     fn drop(&mut self) {
-        self.lock.state.store(
-            if self.is_some {
-                State::AVAILABLE
-            } else {
-                State::FREE
-            },
-            Ordering::Release,
+        // catch corruption of the state, such as by misuse of `raw_state`
+        debug_assert!(
+            self.lock.state.value() & State::FREE == 0,
+            "OptionLock was released while a guard was held"
         );
+        self.lock.owner_release();
+        // SAFETY: the guard holds the lock and tracks the fill state
+        unsafe {
+            self.lock.hold_end();
+            self.lock.raw_release(self.is_some)
+        };
     }
 }
 
+// a borrowed guard must stay on the thread recorded as the owner of the lock
+#[cfg(not(all(feature = "debug_reentrancy", debug_assertions)))]
 unsafe impl<T: Send> Send for OptionGuard<'_, T> {}
 unsafe impl<T: Sync> Sync for OptionGuard<'_, T> {}
+
+/// A guard-like view of an exclusively borrowed [`OptionLock`], which does not
+/// perform any atomic operations.
+pub struct OwnedGuard<'a, T> {
+    lock: &'a mut OptionLock<T>,
+}
+
+impl<T> OwnedGuard<'_, T> {
+    /// Obtain a shared reference to the contained value, if any.
+    pub fn as_ref(&self) -> Option<&T> {
+        if self.lock.is_some() {
+            // SAFETY: the lock is exclusively borrowed and contains a value
+            Some(unsafe { self.lock.value_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Obtain an exclusive reference to the contained value, if any.
+    #[inline]
+    pub fn as_mut(&mut self) -> Option<&mut T> {
+        self.lock.get_mut()
+    }
+
+    /// Check if the lock contains `None`.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        !self.lock.is_some()
+    }
+
+    /// Check if the lock contains `Some(T)`.
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// Replace the value in the lock, returning the previous value, if any.
+    #[inline]
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        self.lock.replace(value)
+    }
+
+    /// Take the current value from the lock, if any.
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        self.lock.take()
+    }
+}
+
+impl<T: Debug> Debug for OwnedGuard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedGuard").field(&self.as_ref()).finish()
+    }
+}
+
+/// An iterator which takes values from an [`OptionLock`], created by
+/// [`OptionLock::drain`].
+pub struct Drain<'a, T> {
+    lock: &'a OptionLock<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.lock.try_take().ok()
+    }
+}
+
+impl<T> Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.lock).finish()
+    }
+}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        // the value is taken before it is dropped, releasing the lock first
+        drop(self.lock.try_take());
+    }
+}
+
+/// A reservation of an empty [`OptionLock`] slot, created by
+/// [`OptionLock::try_reserve`].
+///
+/// If the guard is dropped without being committed or cancelled, then the
+/// slot is released in an empty state. A guard marked as strict using
+/// `FillGuard::set_strict` will then panic, unless the thread is already
+/// panicking.
+pub struct FillGuard<'a, T> {
+    guard: Option<OptionGuard<'a, T>>,
+    strict: bool,
+}
+
+impl<T> FillGuard<'_, T> {
+    /// Store a value in the reserved slot and release the lock.
+    pub fn commit(mut self, value: T) {
+        if let Some(mut guard) = self.guard.take() {
+            guard.replace(value);
+        }
+    }
+
+    /// Release the reserved slot without storing a value.
+    pub fn cancel(mut self) {
+        self.guard.take();
+    }
+
+    /// Set whether dropping the guard without committing or cancelling the
+    /// reservation results in a panic. This is disabled by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+}
+
+impl<T> Debug for FillGuard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FillGuard")
+            .field("strict", &self.strict)
+            .finish()
+    }
+}
+
+impl<T> Drop for FillGuard<'_, T> {
+    fn drop(&mut self) {
+        if let Some(guard) = self.guard.take() {
+            // the slot is released before panicking, so it remains usable
+            drop(guard);
+            #[cfg(feature = "std")]
+            let panicking = ::std::thread::panicking();
+            #[cfg(not(feature = "std"))]
+            let panicking = false;
+            if self.strict && !panicking {
+                panic!("FillGuard was dropped without a value");
+            }
+        }
+    }
+}
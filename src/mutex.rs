@@ -1,25 +1,46 @@
 use core::{
+    borrow::{Borrow, BorrowMut},
     fmt::{self, Debug, Formatter},
+    mem,
     ops::{Deref, DerefMut},
+    pin::Pin,
 };
 
 #[cfg(feature = "alloc")]
-use alloc::sync::Arc;
+use alloc::{rc::Rc, sync::Arc};
 
 use super::{
-    error::{MutexLockError, OptionLockError, PoisonError},
+    error::{MutexLockError, PoisonError},
     lock::{OptionGuard, OptionLock},
 };
 
 #[cfg(feature = "alloc")]
-use super::arc::MutexGuardArc;
+use super::owned::{MutexGuardArc, MutexGuardRc};
+
+/// The result of a `try_lock` operation on a [`Mutex`].
+pub type MutexLockResult<'a, T> = Result<MutexGuard<'a, T>, MutexLockError>;
+
+/// A guard for a [`Mutex`] stored in a `static`, which does not borrow from
+/// any local scope. It may be held across `.await` points and sent between
+/// threads when `T: Send`.
+pub type StaticMutexGuard<T> = MutexGuard<'static, T>;
 
 /// An `OptionLock` with a guaranteed value.
+///
+/// The mutex is a transparent wrapper, sharing the layout of `OptionLock<T>`.
 #[repr(transparent)]
 pub struct Mutex<T> {
     pub(crate) inner: OptionLock<T>,
 }
 
+// The mutex must have the same layout as the lock, as guards for an
+// `Arc<OptionLock>` are converted into guards for an `Arc<Mutex>`.
+const _: () = {
+    use core::mem::{align_of, size_of};
+    assert!(size_of::<Mutex<u64>>() == size_of::<OptionLock<u64>>());
+    assert!(align_of::<Mutex<u64>>() == align_of::<OptionLock<u64>>());
+};
+
 impl<T> Mutex<T> {
     /// Create a new mutex instance.
     pub const fn new(value: T) -> Self {
@@ -28,14 +49,18 @@ impl<T> Mutex<T> {
         }
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub(crate) unsafe fn as_ptr(&self) -> *const T {
-        self.inner.as_ptr()
+    pub(crate) fn from_arc(lock: Arc<OptionLock<T>>) -> Arc<Self> {
+        // SAFETY: the mutex is a transparent wrapper around the lock
+        unsafe { Arc::from_raw(Arc::into_raw(lock) as *const Self) }
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
-    pub(crate) unsafe fn as_mut_ptr(&self) -> *mut T {
-        self.inner.as_mut_ptr()
+    pub(crate) fn from_rc(lock: Rc<OptionLock<T>>) -> Rc<Self> {
+        // SAFETY: the mutex is a transparent wrapper around the lock
+        unsafe { Rc::from_raw(Rc::into_raw(lock) as *const Self) }
     }
 
     /// Check if a guard is held.
@@ -52,7 +77,8 @@ impl<T> Mutex<T> {
 
     /// Get a mutable reference to the contained value
     pub fn get_mut(&mut self) -> &mut T {
-        unsafe { &mut *self.as_mut_ptr() }
+        // SAFETY: the mutex is exclusively borrowed and contains a value
+        unsafe { self.inner.value_mut() }
     }
 
     /// Unwrap an owned mutex instance.
@@ -62,12 +88,8 @@ impl<T> Mutex<T> {
 
     /// Try to acquire an exclusive lock around the contained value
     #[inline]
-    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, MutexLockError> {
-        match self.inner.try_get() {
-            Ok(guard) => Ok(guard),
-            Err(OptionLockError::FillState) => Err(MutexLockError::Poisoned),
-            Err(OptionLockError::Unavailable) => Err(MutexLockError::Unavailable),
-        }
+    pub fn try_lock(&self) -> MutexLockResult<'_, T> {
+        Ok(self.inner.try_get()?)
     }
 
     #[cfg(feature = "alloc")]
@@ -77,6 +99,24 @@ impl<T> Mutex<T> {
             .map(|guard| MutexGuardArc::new(self.clone(), guard))
     }
 
+    #[cfg(feature = "alloc")]
+    /// Try to acquire an exclusive lock for an `Rc<Mutex>`.
+    pub fn try_lock_rc(self: &Rc<Self>) -> Result<MutexGuardRc<T>, MutexLockError> {
+        self.try_lock()
+            .map(|guard| MutexGuardRc::new(self.clone(), guard))
+    }
+
+    /// Try to acquire an exclusive lock for a `static` mutex, producing a guard
+    /// with a `'static` lifetime. This avoids the use of an `Arc` for the
+    /// common `static MUTEX: Mutex<T>` case.
+    ///
+    /// Like other borrowed guards, the guard cannot be sent to another thread
+    /// when the `debug_reentrancy` feature is active.
+    #[inline]
+    pub fn try_lock_static(&'static self) -> Result<StaticMutexGuard<T>, MutexLockError> {
+        self.try_lock()
+    }
+
     /// In a spin loop, wait to acquire the mutex.
     pub fn spin_lock(&self) -> Result<MutexGuard<'_, T>, PoisonError> {
         let guard = self.inner.spin_lock();
@@ -86,6 +126,13 @@ impl<T> Mutex<T> {
             Ok(MutexGuard::new(guard))
         }
     }
+
+    #[cfg(feature = "alloc")]
+    /// In a spin loop, wait to acquire the mutex for an `Arc<Mutex>`.
+    pub fn spin_lock_arc(self: &Arc<Self>) -> Result<MutexGuardArc<T>, PoisonError> {
+        self.spin_lock()
+            .map(|guard| MutexGuardArc::new(self.clone(), guard))
+    }
 }
 
 impl<T: Clone> Mutex<T> {
@@ -113,9 +160,9 @@ impl<T> Debug for Mutex<T> {
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", not(feature = "no_poison")))]
 impl<T> ::std::panic::RefUnwindSafe for Mutex<T> {}
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", not(feature = "no_poison")))]
 impl<T> ::std::panic::UnwindSafe for Mutex<T> {}
 
 /// An exclusive guard for a filled [`OptionLock`]
@@ -126,16 +173,50 @@ impl<'a, T> MutexGuard<'a, T> {
     pub(crate) fn new(guard: OptionGuard<'a, T>) -> Self {
         Self(guard)
     }
+
+    /// Temporarily release the lock while running a function, then spin to
+    /// reacquire it and return a new guard alongside the function's result.
+    ///
+    /// Other threads may access or replace the value while the function runs,
+    /// so any invariants protected by the lock must be checked again after
+    /// this call. If the value was taken by another thread, then the lock is
+    /// released again and a `PoisonError` is returned in place of the guard.
+    /// If the function panics, the lock is left unlocked.
+    pub fn unlocked<R>(slf: Self, f: impl FnOnce() -> R) -> (R, Result<Self, PoisonError>) {
+        let lock = slf.0.lock();
+        // the lock is released here without dropping the value
+        mem::forget(slf);
+        lock.owner_release();
+        // SAFETY: the lock was held by the guard and contains a value
+        unsafe {
+            lock.hold_end();
+            lock.raw_release(true)
+        };
+        let result = f();
+        let relocked = lock.spin_lock().into_mutex_guard().map_err(|_| PoisonError);
+        (result, relocked)
+    }
 }
 
 impl<T> MutexGuard<'_, T> {
     /// Take the value from the mutex. This will result in a `PoisonError` the
     /// next time a lock is attempted.
+    #[track_caller]
     pub fn extract(mut slf: Self) -> T {
         slf.0.take().unwrap()
     }
 
+    /// Take the value from the mutex, pass it to a transformation function,
+    /// and store the result. The lock is held throughout, so the mutex is
+    /// never observed in a poisoned state unless the function panics.
+    #[track_caller]
+    pub fn take_and_refill(slf: &mut Self, f: impl FnOnce(T) -> T) {
+        let value = slf.0.take().unwrap();
+        slf.0.replace(f(value));
+    }
+
     /// Replace the value in the lock, returning the previous value.
+    #[track_caller]
     pub fn replace(slf: &mut Self, value: T) -> T {
         slf.0.replace(value).unwrap()
     }
@@ -144,33 +225,110 @@ impl<T> MutexGuard<'_, T> {
 impl<T> Deref for MutexGuard<'_, T> {
     type Target = T;
 
+    #[track_caller]
     fn deref(&self) -> &Self::Target {
         self.0.as_ref().unwrap()
     }
 }
 
 impl<T> DerefMut for MutexGuard<'_, T> {
+    #[track_caller]
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.0.as_mut().unwrap()
     }
 }
 
+impl<T> AsRef<T> for MutexGuard<'_, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T> AsMut<T> for MutexGuard<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T> Borrow<T> for MutexGuard<'_, T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T> BorrowMut<T> for MutexGuard<'_, T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
 impl<T: Debug> Debug for MutexGuard<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_tuple("MutexGuard").field(&**self).finish()
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", not(feature = "no_poison")))]
 impl<T> Drop for MutexGuard<'_, T> {
     fn drop(&mut self) {
         // Drop the contained value on a panic, because it may not have
-        // been left in a consistent state.
-        if self.0.is_some() && ::std::thread::panicking() {
-            self.0.take();
+        // been left in a consistent state. This is disabled by the
+        // `no_poison` feature. The value is dropped in place, so that a
+        // value pinned by a `PinnedMutexGuard` is never moved.
+        if ::std::thread::panicking() {
+            self.0.drop_value();
         }
     }
 }
 
+// a borrowed guard must stay on the thread recorded as the owner of the lock
+#[cfg(not(all(feature = "debug_reentrancy", debug_assertions)))]
 unsafe impl<T: Send> Send for MutexGuard<'_, T> {}
 unsafe impl<T: Sync> Sync for MutexGuard<'_, T> {}
+
+/// An exclusive guard for a filled [`OptionLock`] providing pinned access to
+/// the contained value, created by [`OptionLock::try_get_pinned`].
+///
+/// The value is not required to be `Unpin`: a future may be stored in the lock
+/// directly and polled through the guard. The caller of `try_get_pinned` is
+/// responsible for ensuring that the value is not moved out of the lock.
+pub struct PinnedMutexGuard<'a, T>(MutexGuard<'a, T>);
+
+impl<'a, T> PinnedMutexGuard<'a, T> {
+    /// # Safety
+    /// The value must not be moved out of the lock until it is dropped.
+    #[inline]
+    pub(crate) unsafe fn new(guard: MutexGuard<'a, T>) -> Self {
+        Self(guard)
+    }
+
+    /// Obtain a pinned exclusive reference to the contained value.
+    #[inline]
+    pub fn as_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: the creator of the guard has promised not to move the value
+        unsafe { Pin::new_unchecked(&mut *self.0) }
+    }
+
+    /// Convert this guard back into an unpinned `MutexGuard`.
+    #[inline]
+    pub fn into_inner(self) -> MutexGuard<'a, T>
+    where
+        T: Unpin,
+    {
+        self.0
+    }
+}
+
+impl<T> Deref for PinnedMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for PinnedMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PinnedMutexGuard").field(&**self).finish()
+    }
+}
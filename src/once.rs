@@ -1,32 +1,368 @@
 use core::{
     cell::Cell,
+    cmp::Ordering,
     fmt::{self, Debug, Display, Formatter},
-    hint::spin_loop,
+    hash::{Hash, Hasher},
+    mem,
     ops::Deref,
+    sync::atomic::{fence, Ordering as AtomicOrdering},
 };
 
-use super::{error::OptionLockError, lock::OptionLock};
+#[cfg(feature = "async")]
+use core::{
+    future::{Future, IntoFuture},
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use super::{
+    backoff::Backoff,
+    error::{InitError, OptionLockError},
+    lock::{OptionLock, State},
+};
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// The addresses of the cells being initialized by the current thread.
+    static INITIALIZING: std::cell::RefCell<alloc::vec::Vec<usize>> =
+        std::cell::RefCell::new(alloc::vec::Vec::new());
+}
+
+/// Records that a cell is being initialized by the current thread, so that a
+/// reentrant initialization can be detected rather than waiting forever. The
+/// check is only performed when the `std` feature is enabled.
+struct InitScope(#[cfg(feature = "std")] usize);
+
+impl InitScope {
+    #[inline]
+    fn enter<T>(cell: &OnceCell<T>) -> Self {
+        #[cfg(feature = "std")]
+        {
+            let addr = cell.addr();
+            INITIALIZING.with(|cells| cells.borrow_mut().push(addr));
+            Self(addr)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = cell;
+            Self()
+        }
+    }
+
+    /// Panic if the cell is being initialized by the current thread.
+    #[inline]
+    fn check<T>(cell: &OnceCell<T>) {
+        #[cfg(feature = "std")]
+        {
+            let addr = cell.addr();
+            if INITIALIZING.with(|cells| cells.borrow().contains(&addr)) {
+                panic!("reentrant initialization of OnceCell");
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = cell;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for InitScope {
+    fn drop(&mut self) {
+        INITIALIZING.with(|cells| {
+            let mut cells = cells.borrow_mut();
+            if let Some(pos) = cells.iter().rposition(|addr| *addr == self.0) {
+                cells.swap_remove(pos);
+            }
+        });
+    }
+}
+
+/// The lock held on a cell while it is initialized. When dropped, including
+/// when the initializer panics or fails, the lock is released in an empty
+/// state and any waiters are woken.
+struct InitGuard<'a, T>(&'a OnceCell<T>);
+
+impl<'a, T> InitGuard<'a, T> {
+    /// Acquire the lock on an empty cell.
+    #[inline]
+    fn acquire(cell: &'a OnceCell<T>) -> Result<Self, OptionLockError> {
+        let lock = &cell.0;
+        let mut state = lock.state.value();
+        loop {
+            if state & !State::WAITING != State::FREE {
+                return Err(if state & State::SOME != 0 {
+                    OptionLockError::FillState
+                } else {
+                    OptionLockError::Unavailable
+                });
+            }
+            // the waiting flag is preserved while the lock is held
+            match lock.state.compare_exchange_weak(
+                state,
+                state & !State::FREE,
+                AtomicOrdering::AcqRel,
+                AtomicOrdering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(s) => state = s,
+            }
+        }
+        lock.observe(State::FREE, 0);
+        lock.owner_acquire();
+        // SAFETY: the lock has been acquired, and is released by this guard
+        unsafe { lock.hold_start() };
+        Ok(Self(cell))
+    }
+
+    /// Store a value in the cell and release the lock.
+    #[inline]
+    fn fill(self, value: T) {
+        // SAFETY: the lock is held and the cell is empty
+        unsafe { self.0 .0.write_value(value) };
+        self.release(State::AVAILABLE);
+    }
+
+    /// Release the lock, storing the given state.
+    #[inline]
+    fn release(self, state: u8) {
+        let cell = self.0;
+        mem::forget(self);
+        cell.release(state);
+    }
+}
+
+impl<T> Drop for InitGuard<'_, T> {
+    fn drop(&mut self) {
+        self.0.release(State::FREE);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "async"))]
+mod parking {
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::thread::{self, Thread};
+
+    #[cfg(feature = "async")]
+    use core::task::Waker;
+
+    use crate::{backoff::Backoff, lock::OptionLock};
+
+    const BUCKETS: usize = 16;
+
+    /// A thread or task waiting for a cell.
+    enum Waiter {
+        #[cfg(feature = "std")]
+        Thread(Thread),
+        #[cfg(feature = "async")]
+        Task(Waker),
+    }
+
+    impl Waiter {
+        fn wake(self) {
+            match self {
+                #[cfg(feature = "std")]
+                Self::Thread(thread) => thread.unpark(),
+                #[cfg(feature = "async")]
+                Self::Task(waker) => waker.wake(),
+            }
+        }
+    }
+
+    type Bucket = OptionLock<Vec<(usize, Waiter)>>;
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY_BUCKET: Bucket = OptionLock::new(Vec::new());
+
+    /// The threads and tasks waiting for a cell, keyed by its address. The
+    /// cells are spread across buckets so that unrelated cells rarely share
+    /// a lock, and a bucket is only accessed while a cell has waiters.
+    static WAITERS: [Bucket; BUCKETS] = [EMPTY_BUCKET; BUCKETS];
+
+    /// Run a function with exclusive access to the bucket for a cell. An
+    /// `OptionGuard` is used rather than a `MutexGuard` so that the bucket is
+    /// not emptied when a cell is released during a panic.
+    fn with_bucket<R>(addr: usize, f: impl FnOnce(&mut Vec<(usize, Waiter)>) -> R) -> R {
+        let bucket = &WAITERS[(addr.wrapping_mul(0x9e37_79b9) >> 16) % BUCKETS];
+        let backoff = Backoff::new();
+        loop {
+            if let Ok(mut guard) = bucket.try_lock() {
+                return f(guard.as_mut().unwrap());
+            }
+            #[cfg(feature = "std")]
+            if backoff.is_completed() {
+                thread::yield_now();
+                continue;
+            }
+            backoff.spin();
+        }
+    }
+
+    /// Park the current thread until woken by `wake_all`. The thread is
+    /// registered before calling `set_waiting`, which marks the cell as having
+    /// waiters and returns `false` if its lock has already been released, so
+    /// that a notification cannot be missed. The thread may also be woken
+    /// spuriously, so the caller must check its condition again.
+    #[cfg(feature = "std")]
+    pub fn park(addr: usize, set_waiting: impl FnOnce() -> bool) {
+        let thread = thread::current();
+        with_bucket(addr, |waiters| {
+            waiters.push((addr, Waiter::Thread(thread.clone())))
+        });
+        if set_waiting() {
+            thread::park();
+        }
+        with_bucket(addr, |waiters| {
+            waiters.retain(|(a, w)| match w {
+                Waiter::Thread(t) => *a != addr || t.id() != thread.id(),
+                #[cfg(feature = "async")]
+                Waiter::Task(_) => true,
+            })
+        });
+    }
+
+    /// Register a task to be woken by `wake_all`, replacing its previous
+    /// registration if any. The caller must then mark the cell as having
+    /// waiters and check its condition again.
+    #[cfg(feature = "async")]
+    pub fn register(addr: usize, prev: Option<&Waker>, waker: &Waker) {
+        with_bucket(addr, |waiters| {
+            if let Some(prev) = prev {
+                remove_task(waiters, addr, prev);
+            }
+            waiters.push((addr, Waiter::Task(waker.clone())));
+        });
+    }
+
+    /// Remove the registration of a task which is no longer waiting.
+    #[cfg(feature = "async")]
+    pub fn deregister(addr: usize, waker: &Waker) {
+        with_bucket(addr, |waiters| remove_task(waiters, addr, waker));
+    }
+
+    #[cfg(feature = "async")]
+    fn remove_task(waiters: &mut Vec<(usize, Waiter)>, addr: usize, waker: &Waker) {
+        let pos = waiters.iter().position(|(a, w)| match w {
+            Waiter::Task(w) => *a == addr && w.will_wake(waker),
+            #[cfg(feature = "std")]
+            Waiter::Thread(_) => false,
+        });
+        if let Some(pos) = pos {
+            waiters.swap_remove(pos);
+        }
+    }
+
+    /// Wake all threads and tasks waiting for a cell.
+    pub fn wake_all(addr: usize) {
+        let mut woken = Vec::new();
+        with_bucket(addr, |waiters| {
+            let mut idx = 0;
+            while idx < waiters.len() {
+                if waiters[idx].0 == addr {
+                    woken.push(waiters.swap_remove(idx).1);
+                } else {
+                    idx += 1;
+                }
+            }
+        });
+        // the waiters are woken after the bucket is released, in case a task
+        // is polled again immediately
+        for waiter in woken {
+            waiter.wake();
+        }
+    }
+}
 
 /// An `Option` value which can be safely written once.
+///
+/// With the `std` feature enabled, a thread waiting for another to initialize
+/// the cell is parked after a short spin, and unparked once the initializer
+/// finishes. The cell only records that it has waiters, so filling a cell
+/// which nothing is waiting on does not touch any shared state.
+///
+/// With the `async` feature enabled, a task may also wait for the cell to be
+/// filled using `wait_async`. The waiting threads and tasks are kept outside
+/// of the cell, which is always a transparent wrapper sharing the layout of
+/// `OptionLock<T>`.
 #[repr(transparent)]
 pub struct OnceCell<T>(OptionLock<T>);
 
+const _: () = {
+    use core::mem::{align_of, size_of};
+    assert!(size_of::<OnceCell<u64>>() == size_of::<OptionLock<u64>>());
+    assert!(align_of::<OnceCell<u64>>() == align_of::<OptionLock<u64>>());
+};
+
 impl<T> OnceCell<T> {
     /// Create a new, empty `OnceCell`.
     pub const fn empty() -> Self {
-        Self(OptionLock::empty())
+        Self::from_lock(OptionLock::empty())
     }
 
     /// Create a `OnceCell` from an owned value.
     pub const fn new(value: T) -> Self {
-        Self(OptionLock::new(value))
+        Self::from_lock(OptionLock::new(value))
+    }
+
+    #[inline]
+    const fn from_lock(lock: OptionLock<T>) -> Self {
+        Self(lock)
+    }
+
+    #[cfg(any(feature = "std", feature = "async"))]
+    #[inline]
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Release the lock held while initializing the cell, storing the given
+    /// state, and wake any waiting threads and tasks.
+    #[inline]
+    fn release(&self, state: u8) {
+        self.0.owner_release();
+        // SAFETY: the lock is held by the caller until the state is stored
+        unsafe { self.0.hold_end() };
+        let prev = self.0.state.swap(state, AtomicOrdering::AcqRel);
+        self.0.observe(prev & !State::WAITING, state);
+        #[cfg(any(feature = "std", feature = "async"))]
+        if prev & State::WAITING != 0 {
+            parking::wake_all(self.addr());
+        }
+    }
+
+    /// Record that a thread or task is waiting for the cell to be filled,
+    /// returning `false` if it has already been filled or poisoned.
+    #[cfg(any(feature = "std", feature = "async"))]
+    fn set_waiting(&self) -> bool {
+        let mut state = self.0.state.value();
+        loop {
+            if state & (State::SOME | State::POISON) != 0 {
+                return false;
+            }
+            if state & State::WAITING != 0 {
+                return true;
+            }
+            // the release ordering makes the registration of the waiter
+            // visible to the thread which releases the lock
+            match self.0.state.compare_exchange_weak(
+                state,
+                state | State::WAITING,
+                AtomicOrdering::Release,
+                AtomicOrdering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(s) => state = s,
+            }
+        }
     }
 
     /// Get a shared reference to the contained value, if any.
+    #[inline]
     pub fn get(&self) -> Option<&T> {
-        if self.0.is_some() {
-            // safe because the value is never reassigned
-            Some(unsafe { &*self.0.as_ptr() })
+        // the acquire load synchronizes with the release of the lock by the
+        // thread which filled the cell, so that the value is visible
+        if self.0.is_some_acquire() {
+            // SAFETY: the value is never reassigned once filled
+            Some(unsafe { self.0.value_ref() })
         } else {
             None
         }
@@ -37,56 +373,214 @@ impl<T> OnceCell<T> {
         self.0.get_mut()
     }
 
+    /// Get a mutable reference to the contained value, initializing it if
+    /// necessary.
+    ///
+    /// Panics if the cell has been poisoned by `get_or_try_init_once`.
+    pub fn get_mut_or_init(&mut self, init: impl FnOnce() -> T) -> &mut T {
+        if self.is_poisoned() {
+            panic!("OnceCell instance has previously been poisoned");
+        }
+        self.0.get_mut_or_insert_with(init)
+    }
+
     /// Get a reference to the contained value, initializing it if necessary.
     /// The initializer will only be run by one thread if multiple are in competition.
+    ///
+    /// Panics if the cell has been poisoned by `get_or_try_init_once`. With the
+    /// `std` feature, this also panics if the initializer attempts to
+    /// initialize the same cell, rather than waiting forever.
+    #[inline]
     pub fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
         if let Some(value) = self.get() {
             return value;
         }
-        match self.0.try_lock_none() {
-            Ok(mut guard) => {
-                let prev = guard.replace(init());
-                assert!(prev.is_none());
-            }
-            Err(OptionLockError::FillState) => {
-                // filled
-            }
-            Err(OptionLockError::Unavailable) => loop {
-                while !self.0.is_some_unlocked() {
-                    spin_loop();
+        self.initialize(init)
+    }
+
+    // the initialization path is kept out of line so that the check for an
+    // initialized value can be inlined into the caller
+    #[cold]
+    #[inline(never)]
+    fn initialize(&self, init: impl FnOnce() -> T) -> &T {
+        loop {
+            match InitGuard::acquire(self) {
+                Ok(guard) => {
+                    let value = {
+                        let _scope = InitScope::enter(self);
+                        init()
+                    };
+                    guard.fill(value);
+                    break;
+                }
+                Err(OptionLockError::FillState) => {
+                    // filled by another thread
+                    fence(AtomicOrdering::Acquire);
+                    break;
                 }
-            },
+                Err(OptionLockError::Unavailable) => {
+                    if self.wait() {
+                        break;
+                    }
+                }
+            }
         }
-        unsafe { &*self.0.as_ptr() }
+        // SAFETY: the cell has been filled and the value is never reassigned
+        unsafe { self.0.value_ref() }
     }
 
     /// Get a reference to the contained value, initializing it if necessary.
     /// The initializer will only be run by one thread if multiple are in competition.
+    ///
+    /// Panics if the cell has been poisoned by `get_or_try_init_once`.
     pub fn get_or_try_init<E>(&self, init: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
         if let Some(value) = self.get() {
             return Ok(value);
         }
-        match self.0.try_lock_none() {
-            Ok(mut guard) => {
-                let prev = guard.replace(init()?);
-                assert!(prev.is_none());
-            }
-            Err(OptionLockError::FillState) => {
-                // filled
+        let mut init = Some(init);
+        loop {
+            match InitGuard::acquire(self) {
+                Ok(guard) => {
+                    let value = {
+                        let _scope = InitScope::enter(self);
+                        (init.take().unwrap())()?
+                    };
+                    guard.fill(value);
+                    break;
+                }
+                Err(OptionLockError::FillState) => {
+                    // filled by another thread
+                    fence(AtomicOrdering::Acquire);
+                    break;
+                }
+                Err(OptionLockError::Unavailable) => {
+                    if self.wait() {
+                        break;
+                    }
+                }
             }
-            Err(OptionLockError::Unavailable) => loop {
-                while !self.0.is_some_unlocked() {
-                    spin_loop();
+        }
+        // SAFETY: the cell has been filled and the value is never reassigned
+        Ok(unsafe { self.0.value_ref() })
+    }
+
+    /// Get a reference to the contained value, initializing it if necessary.
+    /// The initializer will only be run by one thread if multiple are in competition.
+    ///
+    /// If the initializer fails then the cell is poisoned, and its error is
+    /// returned as `InitError::Failed`. Subsequent calls return
+    /// `InitError::Poisoned` without running the initializer.
+    pub fn get_or_try_init_once<E>(
+        &self,
+        init: impl FnOnce() -> Result<T, E>,
+    ) -> Result<&T, InitError<E>> {
+        if let Some(value) = self.get() {
+            return Ok(value);
+        }
+        let mut init = Some(init);
+        loop {
+            match InitGuard::acquire(self) {
+                Ok(guard) => {
+                    let result = {
+                        let _scope = InitScope::enter(self);
+                        (init.take().unwrap())()
+                    };
+                    match result {
+                        Ok(value) => {
+                            guard.fill(value);
+                            break;
+                        }
+                        Err(err) => {
+                            guard.release(State::POISONED);
+                            return Err(InitError::Failed(err));
+                        }
+                    }
+                }
+                Err(OptionLockError::FillState) => {
+                    // filled by another thread
+                    fence(AtomicOrdering::Acquire);
+                    break;
                 }
-            },
+                Err(OptionLockError::Unavailable) => match self.try_wait() {
+                    Some(true) => break,
+                    // the initializer panicked or was run by `get_or_try_init`
+                    // and failed, so try again
+                    Some(false) => (),
+                    None => return Err(InitError::Poisoned),
+                },
+            }
         }
-        Ok(unsafe { &*self.0.as_ptr() })
+        // SAFETY: the cell has been filled and the value is never reassigned
+        Ok(unsafe { self.0.value_ref() })
+    }
+
+    /// Check if a previous call to `get_or_try_init_once` failed.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.0.state.value() & State::POISON != 0
+    }
+
+    /// Wait for another thread to initialize the cell. Returns `false` if the
+    /// lock was released without a value, because the initializer failed or
+    /// panicked, in which case the caller may try to initialize it again.
+    fn wait(&self) -> bool {
+        self.try_wait()
+            .unwrap_or_else(|| panic!("OnceCell instance has previously been poisoned"))
+    }
+
+    /// Wait for another thread to initialize the cell, as for `wait`, returning
+    /// `None` if the cell has been poisoned.
+    fn try_wait(&self) -> Option<bool> {
+        InitScope::check(self);
+        let backoff = Backoff::new();
+        while !self.0.is_some_acquire() {
+            if self.is_poisoned() {
+                return None;
+            }
+            if self.0.state.value() & !State::WAITING == State::FREE {
+                return Some(false);
+            }
+            self.snooze(&backoff);
+        }
+        Some(true)
+    }
+
+    /// Pause while waiting for another thread to initialize the cell. With the
+    /// `std` feature, the thread is parked once the backoff is completed until
+    /// the lock is released.
+    #[inline]
+    fn snooze(&self, backoff: &Backoff) {
+        #[cfg(feature = "std")]
+        if backoff.is_completed() {
+            parking::park(self.addr(), || self.set_waiting() && self.is_locked());
+            return;
+        }
+        backoff.spin();
     }
 
     /// Assign the value of the OnceCell, returning `Some(value)` if
     /// the cell is already locked or populated.
     pub fn set(&self, value: T) -> Result<(), T> {
-        self.0.try_fill(value)
+        match InitGuard::acquire(self) {
+            Ok(guard) => {
+                guard.fill(value);
+                Ok(())
+            }
+            Err(_) => Err(value),
+        }
+    }
+
+    /// Wait for the cell to be initialized by another task, without blocking
+    /// the current thread.
+    ///
+    /// The returned future panics if the cell has been poisoned by
+    /// `get_or_try_init_once`.
+    #[cfg(feature = "async")]
+    pub fn wait_async(&self) -> OnceCellWait<'_, T> {
+        OnceCellWait {
+            cell: self,
+            waker: None,
+        }
     }
 
     /// Extract the inner value.
@@ -106,9 +600,41 @@ impl<T: Clone> Clone for OnceCell<T> {
     }
 }
 
+// Comparisons and hashing use the result of `get`, so a cell which is
+// currently being initialized is treated as empty. An empty cell used as
+// a collection key must not be filled while it is in the collection.
+
+impl<T: PartialEq> PartialEq for OnceCell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Eq> Eq for OnceCell<T> {}
+
+impl<T: PartialOrd> PartialOrd for OnceCell<T> {
+    /// Compare the contained values, ordering an empty cell before any value.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.get().partial_cmp(&other.get())
+    }
+}
+
+impl<T: Ord> Ord for OnceCell<T> {
+    /// Compare the contained values, ordering an empty cell before any value.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get().cmp(&other.get())
+    }
+}
+
+impl<T: Hash> Hash for OnceCell<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get().hash(state)
+    }
+}
+
 impl<T> Default for OnceCell<T> {
     fn default() -> Self {
-        Self(None.into())
+        Self::from_lock(OptionLock::empty())
     }
 }
 
@@ -130,19 +656,75 @@ impl<T: Display> Display for OnceCell<T> {
 
 impl<T> From<T> for OnceCell<T> {
     fn from(data: T) -> Self {
-        Self(data.into())
+        Self::from_lock(data.into())
     }
 }
 
 impl<T> From<Option<T>> for OnceCell<T> {
     fn from(data: Option<T>) -> Self {
-        Self(data.into())
+        Self::from_lock(data.into())
     }
 }
 
 impl<T> From<OptionLock<T>> for OnceCell<T> {
     fn from(lock: OptionLock<T>) -> Self {
-        Self(lock)
+        Self::from_lock(lock)
+    }
+}
+
+/// A future which resolves when a [`OnceCell`] is initialized.
+#[cfg(feature = "async")]
+pub struct OnceCellWait<'a, T> {
+    cell: &'a OnceCell<T>,
+    waker: Option<Waker>,
+}
+
+#[cfg(feature = "async")]
+impl<'a, T> Future for OnceCellWait<'a, T> {
+    type Output = &'a T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let cell = self.cell;
+        if let Some(value) = cell.get() {
+            return Poll::Ready(value);
+        }
+        parking::register(cell.addr(), self.waker.as_ref(), cx.waker());
+        self.waker = Some(cx.waker().clone());
+        cell.set_waiting();
+        // check again in case the cell was filled before the waker was registered
+        if let Some(value) = cell.get() {
+            Poll::Ready(value)
+        } else if cell.is_poisoned() {
+            panic!("OnceCell instance has previously been poisoned");
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Drop for OnceCellWait<'_, T> {
+    fn drop(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            parking::deregister(self.cell.addr(), &waker);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Debug for OnceCellWait<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("OnceCellWait")
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T> IntoFuture for &'a OnceCell<T> {
+    type Output = &'a T;
+    type IntoFuture = OnceCellWait<'a, T>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.wait_async()
     }
 }
 
@@ -164,6 +746,46 @@ impl<T, F> Lazy<T, F> {
     }
 }
 
+impl<T: Clone, F: Clone> Clone for Lazy<T, F> {
+    /// Clone the `Lazy` instance. If the value has already been initialized
+    /// then the new instance carries a clone of the value, not the initializer.
+    /// Otherwise the pending initializer is cloned.
+    fn clone(&self) -> Self {
+        loop {
+            if let Some(value) = self.cell.get() {
+                return Self {
+                    cell: OnceCell::new(value.clone()),
+                    init: Cell::new(None),
+                };
+            }
+            // hold the lock so that the initializer cannot be taken concurrently
+            match InitGuard::acquire(&self.cell) {
+                Ok(guard) => {
+                    // any threads which started waiting for the value while
+                    // the initializer was being cloned are woken on release
+                    // SAFETY: the initializer is only accessed while holding the lock.
+                    // it is not moved out of the cell in case the clone panics
+                    let result = unsafe { &*self.init.as_ptr() }.clone();
+                    drop(guard);
+                    return Self {
+                        cell: OnceCell::empty(),
+                        init: Cell::new(result),
+                    };
+                }
+                Err(OptionLockError::FillState) => {
+                    // filled
+                }
+                Err(OptionLockError::Unavailable) => {
+                    let backoff = Backoff::new();
+                    while self.cell.is_locked() {
+                        backoff.spin();
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl<T: Debug, F> Debug for Lazy<T, F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("Lazy")
@@ -179,10 +801,52 @@ impl<T: Display, F: FnOnce() -> T> Display for Lazy<T, F> {
     }
 }
 
+impl<T, F> Lazy<T, F> {
+    /// Check if the initializer has run.
+    #[inline]
+    pub fn is_initialized(this: &Self) -> bool {
+        this.cell.get().is_some()
+    }
+}
+
 impl<T, F: FnOnce() -> T> Lazy<T, F> {
     /// Ensure that the initializer has run
+    #[inline]
+    #[track_caller]
     pub fn force(this: &Self) -> &T {
-        this.cell.get_or_init(|| (this.init.take().unwrap())())
+        if let Some(value) = this.cell.get() {
+            return value;
+        }
+        Self::initialize(this)
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn initialize(this: &Self) -> &T {
+        match this
+            .cell
+            .get_or_try_init(|| this.init.take().map(|init| init()).ok_or(()))
+        {
+            Ok(value) => value,
+            Err(()) => panic!("Lazy instance has previously been poisoned"),
+        }
+    }
+
+    /// Ensure that the initializer has run and obtain a mutable reference
+    /// to the value.
+    #[track_caller]
+    pub fn force_mut(this: &mut Self) -> &mut T {
+        let lock = &mut this.cell.0;
+        if lock.get_mut().is_none() {
+            let init = this
+                .init
+                .get_mut()
+                .take()
+                .expect("Lazy instance has previously been poisoned");
+            lock.replace(init());
+        }
+        lock.get_mut().unwrap()
     }
 }
 
@@ -195,7 +859,103 @@ impl<T: Default> Default for Lazy<T> {
 impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
     type Target = T;
 
+    #[inline]
+    #[track_caller]
     fn deref(&self) -> &T {
         Lazy::force(self)
     }
 }
+
+/// A lock with a one-time initializer, which may be stored in a `static`
+/// when the initial value cannot be constructed in a `const` context.
+///
+/// Unlike `Lazy`, the value remains mutable: the `LazyLock` dereferences to
+/// the initialized `OptionLock`, so that guards may be acquired with
+/// `try_lock` or `try_get`.
+///
+/// ```
+/// use option_lock::LazyLock;
+///
+/// static NAMES: LazyLock<Vec<String>> = LazyLock::new(|| vec!["a".to_string()]);
+///
+/// NAMES.try_get().unwrap().push("b".to_string());
+/// assert_eq!(NAMES.try_clone().unwrap(), ["a", "b"]);
+/// ```
+pub struct LazyLock<T, F = fn() -> T> {
+    cell: OnceCell<OptionLock<T>>,
+    init: Cell<Option<F>>,
+}
+
+unsafe impl<T, F: Send> Sync for LazyLock<T, F> where OnceCell<OptionLock<T>>: Sync {}
+
+impl<T, F> LazyLock<T, F> {
+    /// Create a new LazyLock instance
+    pub const fn new(init: F) -> Self {
+        Self {
+            cell: OnceCell::empty(),
+            init: Cell::new(Some(init)),
+        }
+    }
+
+    /// Check if the initializer has run.
+    #[inline]
+    pub fn is_initialized(this: &Self) -> bool {
+        this.cell.get().is_some()
+    }
+}
+
+impl<T, F: FnOnce() -> T> LazyLock<T, F> {
+    /// Ensure that the initializer has run, and obtain a reference to the
+    /// initialized lock.
+    #[inline]
+    #[track_caller]
+    pub fn force(this: &Self) -> &OptionLock<T> {
+        if let Some(lock) = this.cell.get() {
+            return lock;
+        }
+        Self::initialize(this)
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[track_caller]
+    fn initialize(this: &Self) -> &OptionLock<T> {
+        match this.cell.get_or_try_init(|| {
+            this.init
+                .take()
+                .map(|init| OptionLock::new(init()))
+                .ok_or(())
+        }) {
+            Ok(lock) => lock,
+            Err(()) => panic!("LazyLock instance has previously been poisoned"),
+        }
+    }
+}
+
+impl<T> Default for LazyLock<T>
+where
+    T: Default,
+{
+    fn default() -> LazyLock<T> {
+        LazyLock::new(T::default)
+    }
+}
+
+impl<T, F> Debug for LazyLock<T, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyLock")
+            .field("lock", &self.cell.get())
+            .field("init", &"..")
+            .finish()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyLock<T, F> {
+    type Target = OptionLock<T>;
+
+    #[inline]
+    #[track_caller]
+    fn deref(&self) -> &OptionLock<T> {
+        LazyLock::force(self)
+    }
+}
@@ -0,0 +1,341 @@
+use alloc::{rc::Rc, sync::Arc};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    fmt::{self, Debug, Formatter},
+    mem,
+    ops::{Deref, DerefMut},
+    ptr,
+};
+
+use super::error::OptionLockError;
+use super::lock::{OptionGuard, OptionLock};
+use super::mutex::{Mutex, MutexGuard};
+
+mod sealed {
+    /// The names of the guard types for each pointer type, used in debug output.
+    pub trait Sealed {
+        const OPTION_GUARD: &'static str;
+        const MUTEX_GUARD: &'static str;
+        const MAPPED_MUTEX_GUARD: &'static str;
+    }
+}
+
+impl<L> sealed::Sealed for Arc<L> {
+    const OPTION_GUARD: &'static str = "OptionGuardArc";
+    const MUTEX_GUARD: &'static str = "MutexGuardArc";
+    const MAPPED_MUTEX_GUARD: &'static str = "MappedMutexGuardArc";
+}
+
+impl<L> sealed::Sealed for Rc<L> {
+    const OPTION_GUARD: &'static str = "OptionGuardRc";
+    const MUTEX_GUARD: &'static str = "MutexGuardRc";
+    const MAPPED_MUTEX_GUARD: &'static str = "MappedMutexGuardRc";
+}
+
+/// A shared pointer to a lock which may be held by an owned guard, allowing
+/// the guard to outlive the borrow of the lock. This trait is implemented for
+/// `Arc` and `Rc`, and cannot be implemented outside of this crate.
+///
+/// ```
+/// use option_lock::{CloneableHandle, OptionLock};
+/// use std::rc::Rc;
+///
+/// let lock = Rc::new(OptionLock::new(1));
+/// let mut guard = lock.try_lock_owned().unwrap();
+/// assert_eq!(guard.replace(2), Some(1));
+/// drop(guard);
+/// assert_eq!(lock.try_take(), Ok(2));
+/// ```
+pub trait CloneableHandle: Clone + Deref + sealed::Sealed {
+    /// Try to acquire an exclusive lock, returning an owned guard which holds
+    /// a clone of this handle.
+    fn try_lock_owned<T>(&self) -> Result<OwnedOptionGuard<T, Self>, OptionLockError>
+    where
+        Self: Deref<Target = OptionLock<T>>,
+    {
+        self.try_lock()
+            .map(|guard| OwnedOptionGuard::new(self.clone(), guard))
+    }
+}
+
+impl<L> CloneableHandle for Arc<L> {}
+
+impl<L> CloneableHandle for Rc<L> {}
+
+/// A write guard for the value of an [`OptionLock`] which holds a shared
+/// pointer to the lock.
+pub struct OwnedOptionGuard<T, P: CloneableHandle<Target = OptionLock<T>>> {
+    lock: P,
+    filled: bool,
+}
+
+/// A write guard for the value of an [`Arc<OptionLock>`]
+pub type OptionGuardArc<T> = OwnedOptionGuard<T, Arc<OptionLock<T>>>;
+
+/// A write guard for the value of an [`Rc<OptionLock>`]
+pub type OptionGuardRc<T> = OwnedOptionGuard<T, Rc<OptionLock<T>>>;
+
+impl<T, P: CloneableHandle<Target = OptionLock<T>>> OwnedOptionGuard<T, P> {
+    #[inline]
+    pub(crate) fn new(lock: P, guard: OptionGuard<'_, T>) -> Self {
+        let result = Self {
+            lock,
+            filled: guard.is_some(),
+        };
+        mem::forget(guard);
+        // owned guards are not tied to the thread which acquired the lock,
+        // so they are not tracked
+        result.lock.owner_release();
+        result
+    }
+
+    /// Get a pointer to the locked `OptionLock` instance. This may be compared
+    /// with the result of `Arc::as_ptr` or `Rc::as_ptr` to check the source of
+    /// the guard.
+    #[inline]
+    pub fn lock_ptr(&self) -> *const OptionLock<T> {
+        &*self.lock
+    }
+
+    /// Obtain a shared reference to the contained value, if any.
+    pub fn as_ref(&self) -> Option<&T> {
+        if self.filled {
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(unsafe { self.lock.value_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Obtain an exclusive reference to the contained value, if any.
+    pub fn as_mut_ref(&mut self) -> Option<&mut T> {
+        if self.filled {
+            // SAFETY: the guard holds the lock and the slot contains a value
+            Some(unsafe { self.lock.value_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Check if the lock contains `None`.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        !self.filled
+    }
+
+    /// Check if the lock contains `Some(T)`.
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        self.filled
+    }
+
+    /// Replace the value in the lock, returning the previous value, if any.
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        let ret = if self.filled {
+            // SAFETY: the guard holds the lock and the slot contains a value,
+            // which is immediately replaced
+            Some(unsafe { self.lock.read_value() })
+        } else {
+            self.filled = true;
+            None
+        };
+        // SAFETY: the guard holds the lock and the slot is now empty
+        unsafe { self.lock.write_value(value) };
+        ret
+    }
+
+    /// Take the current value from the lock, if any.
+    pub fn take(&mut self) -> Option<T> {
+        if self.filled {
+            self.filled = false;
+            // SAFETY: the guard holds the lock and the slot contained a value
+            Some(unsafe { self.lock.read_value() })
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Debug, P: CloneableHandle<Target = OptionLock<T>>> Debug for OwnedOptionGuard<T, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(P::OPTION_GUARD)
+            .field(&self.as_ref())
+            .finish()
+    }
+}
+
+impl<T, P: CloneableHandle<Target = OptionLock<T>>> fmt::Pointer for OwnedOptionGuard<T, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.lock_ptr(), f)
+    }
+}
+
+impl<T, P: CloneableHandle<Target = OptionLock<T>>> Drop for OwnedOptionGuard<T, P> {
+    fn drop(&mut self) {
+        let _ = OptionGuard::new(&self.lock, self.filled);
+    }
+}
+
+unsafe impl<T: Send, P: CloneableHandle<Target = OptionLock<T>> + Send> Send
+    for OwnedOptionGuard<T, P>
+{
+}
+unsafe impl<T: Sync, P: CloneableHandle<Target = OptionLock<T>> + Sync> Sync
+    for OwnedOptionGuard<T, P>
+{
+}
+
+/// A write guard for a [`Mutex`] which holds a shared pointer to the mutex.
+pub struct OwnedMutexGuard<T, P: CloneableHandle<Target = Mutex<T>>> {
+    lock: P,
+}
+
+/// A write guard for an [`Arc<Mutex>`]
+pub type MutexGuardArc<T> = OwnedMutexGuard<T, Arc<Mutex<T>>>;
+
+/// A write guard for an [`Rc<Mutex>`]
+pub type MutexGuardRc<T> = OwnedMutexGuard<T, Rc<Mutex<T>>>;
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> OwnedMutexGuard<T, P> {
+    #[inline]
+    pub(crate) fn new(lock: P, guard: MutexGuard<'_, T>) -> Self {
+        let result = Self { lock };
+        mem::forget(guard);
+        // owned guards are not tied to the thread which acquired the lock,
+        // so they are not tracked
+        result.lock.inner.owner_release();
+        result
+    }
+
+    /// Get a pointer to the locked `OptionLock` instance. This may be compared
+    /// with the result of `Arc::as_ptr` or `Rc::as_ptr` to check the source of
+    /// the guard.
+    #[inline]
+    pub fn lock_ptr(&self) -> *const OptionLock<T> {
+        &self.lock.inner
+    }
+
+    /// Replace the value in the lock, returning the previous value.
+    pub fn replace(&mut self, value: T) -> T {
+        // SAFETY: the guard holds the lock and the mutex always contains a value
+        mem::replace(unsafe { self.lock.inner.value_mut() }, value)
+    }
+
+    /// Project the guard onto a component of the locked value. The lock is
+    /// held until the returned guard is dropped.
+    pub fn map<U>(mut self, f: impl FnOnce(&mut T) -> &mut U) -> MappedOwnedMutexGuard<T, U, P> {
+        let value = f(&mut *self) as *mut U;
+        // SAFETY: the guard is forgotten, so the pointer is not dropped twice
+        let lock = unsafe { ptr::read(&self.lock) };
+        mem::forget(self);
+        MappedOwnedMutexGuard { lock, value }
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> Deref for OwnedMutexGuard<T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the guard holds the lock and the mutex always contains a value
+        unsafe { self.lock.inner.value_ref() }
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> DerefMut for OwnedMutexGuard<T, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the guard holds the lock and the mutex always contains a value
+        unsafe { self.lock.inner.value_mut() }
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> AsRef<T> for OwnedMutexGuard<T, P> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> AsMut<T> for OwnedMutexGuard<T, P> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> Borrow<T> for OwnedMutexGuard<T, P> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> BorrowMut<T> for OwnedMutexGuard<T, P> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: Debug, P: CloneableHandle<Target = Mutex<T>>> Debug for OwnedMutexGuard<T, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(P::MUTEX_GUARD).field(&**self).finish()
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> fmt::Pointer for OwnedMutexGuard<T, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.lock_ptr(), f)
+    }
+}
+
+impl<T, P: CloneableHandle<Target = Mutex<T>>> Drop for OwnedMutexGuard<T, P> {
+    fn drop(&mut self) {
+        let _ = OptionGuard::new(&self.lock.inner, true);
+    }
+}
+
+unsafe impl<T: Send, P: CloneableHandle<Target = Mutex<T>> + Send> Send for OwnedMutexGuard<T, P> {}
+unsafe impl<T: Sync, P: CloneableHandle<Target = Mutex<T>> + Sync> Sync for OwnedMutexGuard<T, P> {}
+
+/// A write guard for a component of the value of a [`Mutex`], which holds a
+/// shared pointer to the mutex.
+pub struct MappedOwnedMutexGuard<T, U, P: CloneableHandle<Target = Mutex<T>>> {
+    lock: P,
+    value: *mut U,
+}
+
+/// A write guard for a component of the value of an [`Arc<Mutex>`]
+pub type MappedMutexGuardArc<T, U> = MappedOwnedMutexGuard<T, U, Arc<Mutex<T>>>;
+
+impl<T, U, P: CloneableHandle<Target = Mutex<T>>> Deref for MappedOwnedMutexGuard<T, U, P> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the value is borrowed from the locked mutex
+        unsafe { &*self.value }
+    }
+}
+
+impl<T, U, P: CloneableHandle<Target = Mutex<T>>> DerefMut for MappedOwnedMutexGuard<T, U, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the value is borrowed from the locked mutex
+        unsafe { &mut *self.value }
+    }
+}
+
+impl<T, U: Debug, P: CloneableHandle<Target = Mutex<T>>> Debug for MappedOwnedMutexGuard<T, U, P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple(P::MAPPED_MUTEX_GUARD).field(&**self).finish()
+    }
+}
+
+impl<T, U, P: CloneableHandle<Target = Mutex<T>>> Drop for MappedOwnedMutexGuard<T, U, P> {
+    fn drop(&mut self) {
+        let _ = OptionGuard::new(&self.lock.inner, true);
+    }
+}
+
+unsafe impl<T: Send, U: Send, P: CloneableHandle<Target = Mutex<T>> + Send> Send
+    for MappedOwnedMutexGuard<T, U, P>
+{
+}
+unsafe impl<T: Sync, U: Sync, P: CloneableHandle<Target = Mutex<T>> + Sync> Sync
+    for MappedOwnedMutexGuard<T, U, P>
+{
+}
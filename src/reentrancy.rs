@@ -0,0 +1,38 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+std::thread_local! {
+    static THREAD_MARKER: u8 = 0;
+}
+
+/// Obtain a non-zero value which is unique to each running thread.
+#[inline]
+fn current_thread() -> usize {
+    THREAD_MARKER.with(|marker| marker as *const u8 as usize)
+}
+
+/// Tracks the thread currently holding a borrowed guard for a lock.
+pub(crate) struct Owner(AtomicUsize);
+
+impl Owner {
+    pub const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    #[inline]
+    pub fn acquire(&self) {
+        self.0.store(current_thread(), Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn release(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+
+    /// Panic if the current thread already holds the lock.
+    #[inline]
+    pub fn check(&self) {
+        if self.0.load(Ordering::Relaxed) == current_thread() {
+            panic!("OptionLock is already held by the current thread");
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use core::fmt::{self, Debug, Formatter};
+
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+
+use super::{error::OptionLockError, lock::OptionLock};
+
+/// A handle to an [`OptionLock`] which may only store values.
+pub struct Filler<'a, T> {
+    lock: &'a OptionLock<T>,
+}
+
+impl<'a, T> Filler<'a, T> {
+    #[inline]
+    pub(crate) fn new(lock: &'a OptionLock<T>) -> Self {
+        Self { lock }
+    }
+
+    /// Try to store a value, if the slot is currently empty and a lock can be acquired.
+    #[inline]
+    pub fn try_fill(&self, value: T) -> Result<(), T> {
+        self.lock.try_fill(value)
+    }
+
+    /// Store the result of an initializer function if the slot is currently empty
+    /// and a lock can be acquired.
+    #[inline]
+    pub fn try_fill_with(&self, f: impl FnOnce() -> T) -> Result<(), OptionLockError> {
+        self.lock.try_fill_with(f)
+    }
+
+    /// In a spin loop, wait for an empty slot and store a value.
+    #[inline]
+    pub fn spin_fill(&self, value: T) {
+        self.lock.spin_fill(value)
+    }
+}
+
+impl<T> Debug for Filler<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Filler").field(&self.lock).finish()
+    }
+}
+
+/// A handle to an [`OptionLock`] which may only take values.
+pub struct Taker<'a, T> {
+    lock: &'a OptionLock<T>,
+}
+
+impl<'a, T> Taker<'a, T> {
+    #[inline]
+    pub(crate) fn new(lock: &'a OptionLock<T>) -> Self {
+        Self { lock }
+    }
+
+    /// Try to take a stored value from the lock.
+    #[inline]
+    pub fn try_take(&self) -> Result<T, OptionLockError> {
+        self.lock.try_take()
+    }
+
+    /// In a spin loop, wait to take a value from the lock.
+    #[inline]
+    pub fn spin_take(&self) -> T {
+        self.lock.spin_take()
+    }
+}
+
+impl<T> Debug for Taker<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Taker").field(&self.lock).finish()
+    }
+}
+
+/// A handle to an [`Arc<OptionLock>`] which may only store values.
+#[cfg(feature = "alloc")]
+pub struct FillerArc<T> {
+    lock: Arc<OptionLock<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> FillerArc<T> {
+    #[inline]
+    pub(crate) fn new(lock: Arc<OptionLock<T>>) -> Self {
+        Self { lock }
+    }
+
+    /// Try to store a value, if the slot is currently empty and a lock can be acquired.
+    #[inline]
+    pub fn try_fill(&self, value: T) -> Result<(), T> {
+        self.lock.try_fill(value)
+    }
+
+    /// Store the result of an initializer function if the slot is currently empty
+    /// and a lock can be acquired.
+    #[inline]
+    pub fn try_fill_with(&self, f: impl FnOnce() -> T) -> Result<(), OptionLockError> {
+        self.lock.try_fill_with(f)
+    }
+
+    /// In a spin loop, wait for an empty slot and store a value.
+    #[inline]
+    pub fn spin_fill(&self, value: T) {
+        self.lock.spin_fill(value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Debug for FillerArc<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FillerArc").field(&self.lock).finish()
+    }
+}
+
+/// A handle to an [`Arc<OptionLock>`] which may only take values.
+#[cfg(feature = "alloc")]
+pub struct TakerArc<T> {
+    lock: Arc<OptionLock<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> TakerArc<T> {
+    #[inline]
+    pub(crate) fn new(lock: Arc<OptionLock<T>>) -> Self {
+        Self { lock }
+    }
+
+    /// Try to take a stored value from the lock.
+    #[inline]
+    pub fn try_take(&self) -> Result<T, OptionLockError> {
+        self.lock.try_take()
+    }
+
+    /// In a spin loop, wait to take a value from the lock.
+    #[inline]
+    pub fn spin_take(&self) -> T {
+        self.lock.spin_take()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Debug for TakerArc<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TakerArc").field(&self.lock).finish()
+    }
+}
@@ -0,0 +1,60 @@
+//! Bit flags making up the raw state of an [`OptionLock`](crate::OptionLock),
+//! as returned by [`OptionLock::raw_state`](crate::OptionLock::raw_state).
+//!
+//! A lock which is held has the `FREE` bit cleared, so a locked and empty
+//! slot has a state of `0` and a locked and filled slot has a state of `SOME`.
+
+/// Set when the lock is not held.
+pub const FREE: u8 = 1 << 0;
+
+/// Set when the lock contains a value.
+pub const SOME: u8 = 1 << 1;
+
+/// The state of an unlocked slot containing a value.
+pub const AVAILABLE: u8 = FREE | SOME;
+
+#[cfg(feature = "instrument")]
+/// A function which is notified of each state transition of an
+/// [`OptionLock`](crate::OptionLock), with the previous and new states.
+pub type LockObserver = fn(LockState, LockState);
+
+#[cfg(feature = "instrument")]
+/// The state of an [`OptionLock`](crate::OptionLock), as reported to a
+/// [`LockObserver`].
+///
+/// The following transitions are reported:
+/// - `Empty` to `LockedEmpty`, or `Filled` to `LockedFilled`, when a lock is
+///   acquired.
+/// - `LockedEmpty` to `Empty`, or `LockedFilled` to `Filled`, when a lock is
+///   released, according to whether it contains a value at that time. Filling
+///   or taking the value is reflected in the state of the lock on release.
+/// - `LockedEmpty` to `Poisoned`, when the initializer of a `OnceCell` fails.
+///
+/// Changes made through an exclusive reference or `raw_state` are not
+/// reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockState {
+    /// The lock is not held and contains no value
+    Empty,
+    /// The lock is not held and contains a value
+    Filled,
+    /// The lock is held and contained no value when it was acquired or released
+    LockedEmpty,
+    /// The lock is held and contained a value when it was acquired or released
+    LockedFilled,
+    /// The lock belongs to a `OnceCell` whose initializer failed
+    Poisoned,
+}
+
+#[cfg(feature = "instrument")]
+impl LockState {
+    pub(crate) fn from_raw(state: u8) -> Self {
+        match state {
+            FREE => Self::Empty,
+            AVAILABLE => Self::Filled,
+            0 => Self::LockedEmpty,
+            SOME => Self::LockedFilled,
+            _ => Self::Poisoned,
+        }
+    }
+}
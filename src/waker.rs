@@ -0,0 +1,72 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    task::{Context, Poll, Waker},
+};
+
+use super::lock::OptionLock;
+
+enum SlotState<T> {
+    Ready(T),
+    Wake(Waker),
+}
+
+/// A slot for a single value, which wakes a registered task when the value
+/// is stored.
+pub struct WakeSlot<T> {
+    state: OptionLock<SlotState<T>>,
+}
+
+impl<T> WakeSlot<T> {
+    /// Create a new, empty slot.
+    pub const fn new() -> Self {
+        Self {
+            state: OptionLock::empty(),
+        }
+    }
+
+    /// Store a value in the slot, replacing any value which has not yet been
+    /// taken, and wake the registered task, if any. This spins while the slot
+    /// is being polled by another thread.
+    pub fn set(&self, value: T) {
+        let prev = self.state.spin_lock().replace(SlotState::Ready(value));
+        // the task is woken after the lock is released
+        if let Some(SlotState::Wake(waker)) = prev {
+            waker.wake();
+        }
+    }
+
+    /// Take the stored value, or register the current task to be woken when
+    /// a value is stored. Only the most recently registered task is woken.
+    pub fn poll(&self, cx: &mut Context<'_>) -> Poll<T> {
+        match self.state.try_lock() {
+            Ok(mut guard) => match guard.take() {
+                Some(SlotState::Ready(value)) => Poll::Ready(value),
+                Some(SlotState::Wake(waker)) if waker.will_wake(cx.waker()) => {
+                    guard.replace(SlotState::Wake(waker));
+                    Poll::Pending
+                }
+                _ => {
+                    guard.replace(SlotState::Wake(cx.waker().clone()));
+                    Poll::Pending
+                }
+            },
+            Err(_) => {
+                // a value is currently being stored, so poll again
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Default for WakeSlot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for WakeSlot<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "WakeSlot({:?})", &self.state.state)
+    }
+}
@@ -244,3 +244,2178 @@ fn lazy_static() {
     static CELL: Lazy<i32> = Lazy::new(|| 99);
     assert_eq!(*CELL, 99);
 }
+
+#[test]
+fn lazy_clone() {
+    let lazy = Lazy::new(|| 5);
+    let cloned = lazy.clone();
+    assert_eq!(*cloned, 5);
+    assert_eq!(*lazy, 5);
+
+    let forced = lazy.clone();
+    assert_eq!(*forced, 5);
+}
+
+#[test]
+fn backoff_completed() {
+    let backoff = Backoff::new();
+    assert!(!backoff.is_completed());
+    while !backoff.is_completed() {
+        backoff.spin();
+    }
+    backoff.spin();
+    assert!(backoff.is_completed());
+    backoff.reset();
+    assert!(!backoff.is_completed());
+}
+
+#[test]
+fn rc_lock_guard() {
+    use std::rc::Rc;
+
+    let a = Rc::new(OptionLock::from(1));
+    let mut guard = a.try_lock_rc().unwrap();
+    assert!(a.is_locked());
+    assert_eq!(a.try_lock().unwrap_err(), OptionLockError::Unavailable);
+    assert_eq!(guard.as_ref(), Some(&1));
+    guard.replace(2);
+    drop(guard);
+    assert!(!a.is_locked());
+
+    let mut guard = a.try_get_rc().unwrap();
+    assert_eq!(*guard, 2);
+    *guard += 1;
+    assert_eq!(format!("{:?}", &guard), "MutexGuardRc(3)");
+    drop(guard);
+    assert_eq!(a.try_take(), Ok(3));
+    assert!(a.try_lock_empty_rc().unwrap().is_none());
+
+    let m = Rc::new(Mutex::new(5));
+    let guard = m.try_lock_rc().unwrap();
+    assert!(m.is_locked());
+    assert_eq!(*guard, 5);
+    drop(guard);
+    assert!(!m.is_locked());
+}
+
+#[test]
+fn try_lock_owned() {
+    use std::rc::Rc;
+
+    let lock = Rc::new(OptionLock::new(1));
+    let mut guard: OptionGuardRc<i32> = lock.try_lock_owned().unwrap();
+    assert_eq!(
+        lock.try_lock_owned().unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    assert_eq!(guard.take(), Some(1));
+    assert_eq!(format!("{:?}", &guard), "OptionGuardRc(None)");
+    drop(guard);
+    assert!(lock.is_none_unlocked());
+
+    let lock = Arc::new(OptionLock::new(2));
+    let guard: OptionGuardArc<i32> = lock.try_lock_owned().unwrap();
+    assert_eq!(guard.lock_ptr(), Arc::as_ptr(&lock));
+    std::thread::spawn(move || drop(guard)).join().unwrap();
+    assert_eq!(lock.try_take(), Ok(2));
+
+    // projection is shared by the owned mutex guards
+    let m = Rc::new(Mutex::new((1, 2)));
+    let mut guard = m.try_lock_rc().unwrap().map(|v| &mut v.1);
+    *guard += 1;
+    assert_eq!(format!("{:?}", &guard), "MappedMutexGuardRc(3)");
+    drop(guard);
+    assert_eq!(m.try_copy(), Ok((1, 3)));
+}
+
+#[test]
+fn mutex_take_and_refill() {
+    let m = Mutex::new(vec![1, 2]);
+    let mut guard = m.try_lock().unwrap();
+    MutexGuard::take_and_refill(&mut guard, |mut v| {
+        v.push(3);
+        v
+    });
+    assert_eq!(*guard, vec![1, 2, 3]);
+    drop(guard);
+    assert!(!m.is_poisoned());
+    assert_eq!(m.try_clone(), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn mutex_guard_as_ref() {
+    use std::borrow::{Borrow, BorrowMut};
+
+    fn str_len(s: impl AsRef<str>) -> usize {
+        s.as_ref().len()
+    }
+
+    let m = Arc::new(Mutex::new(String::from("abc")));
+    let mut guard = m.try_lock().unwrap();
+    assert_eq!(str_len(guard.as_ref()), 3);
+    guard.as_mut().push('d');
+    BorrowMut::<String>::borrow_mut(&mut guard).push('e');
+    assert_eq!(Borrow::<String>::borrow(&guard), "abcde");
+    drop(guard);
+
+    let mut guard = m.try_lock_arc().unwrap();
+    guard.as_mut().push('f');
+    assert_eq!(str_len(guard.as_ref()), 6);
+}
+
+#[test]
+fn option_lock_scope() {
+    let a = OptionLock::<i32>::empty();
+    assert_eq!(a.get_scope(|g| **g), Err(OptionLockError::FillState));
+    assert_eq!(a.scope(|g| g.replace(5)), Ok(None));
+    assert_eq!(
+        a.scope(|g| {
+            assert_eq!(a.scope(|_| ()), Err(OptionLockError::Unavailable));
+            g.is_some()
+        }),
+        Ok(true)
+    );
+    assert_eq!(
+        a.get_scope(|g| {
+            **g += 1;
+            **g
+        }),
+        Ok(6)
+    );
+    assert!(!a.is_locked());
+
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        a.scope(|_| panic!("expected")).unwrap();
+    }));
+    assert!(res.is_err());
+    assert!(!a.is_locked());
+    assert_eq!(a.try_take(), Ok(6));
+
+    // a panic within `get_scope` drops the value, as for any `MutexGuard`
+    a.try_fill(7).unwrap();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        a.get_scope(|_| panic!("expected")).unwrap();
+    }));
+    assert!(res.is_err());
+    if cfg!(all(feature = "std", not(feature = "no_poison"))) {
+        assert!(a.is_none_unlocked());
+    } else {
+        assert_eq!(a.try_take(), Ok(7));
+    }
+}
+
+#[test]
+#[allow(clippy::mutable_key_type)]
+fn once_cell_ord() {
+    use std::collections::{BTreeSet, HashSet};
+
+    let set: BTreeSet<OnceCell<u32>> = vec![
+        OnceCell::new(3),
+        OnceCell::empty(),
+        OnceCell::new(1),
+        OnceCell::new(3),
+    ]
+    .into_iter()
+    .collect();
+    let values: Vec<Option<u32>> = set.iter().map(|c| c.get().copied()).collect();
+    assert_eq!(values, vec![None, Some(1), Some(3)]);
+
+    let hashed: HashSet<OnceCell<u32>> = set.into_iter().collect();
+    assert!(hashed.contains(&OnceCell::new(1)));
+    assert!(hashed.contains(&OnceCell::empty()));
+    assert!(!hashed.contains(&OnceCell::new(2)));
+}
+
+#[test]
+fn option_lock_try_modify() {
+    let a = OptionLock::<i32>::empty();
+    assert_eq!(a.try_modify(|_| unreachable!()), Ok(None::<()>));
+    a.try_fill(1).unwrap();
+    assert_eq!(
+        a.try_modify(|v| {
+            *v += 1;
+            *v * 10
+        }),
+        Ok(Some(20))
+    );
+    let guard = a.try_lock().unwrap();
+    assert_eq!(a.try_modify(|v| *v), Err(OptionLockError::Unavailable));
+    drop(guard);
+    assert_eq!(a.try_take(), Ok(2));
+}
+
+#[test]
+fn option_lock_drain_slice() {
+    let slots: Vec<OptionLock<usize>> = (0..4).map(|_| OptionLock::empty()).collect();
+    slots[1].try_fill(1).unwrap();
+    slots[3].try_fill(3).unwrap();
+    assert_eq!(
+        OptionLock::drain_slice(&slots).collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    assert_eq!(OptionLock::drain_slice(&slots).count(), 0);
+
+    slots[0].try_fill(0).unwrap();
+    slots[2].try_fill(2).unwrap();
+    let guard = slots[2].try_lock().unwrap();
+    assert_eq!(OptionLock::drain_slice(&slots).collect::<Vec<_>>(), vec![0]);
+    slots[3].try_fill(3).unwrap();
+    // no values are taken while a slot is contended
+    assert_eq!(
+        OptionLock::try_drain_slice(&slots),
+        Err(OptionLockError::Unavailable)
+    );
+    assert!(slots[3].is_some_unlocked());
+    drop(guard);
+    assert_eq!(OptionLock::try_drain_slice(&slots), Ok(vec![2, 3]));
+}
+
+#[test]
+fn owned_get_mut_or_insert() {
+    let mut lock = OptionLock::<u32>::empty();
+    *lock.get_mut_or_insert(1) += 1;
+    assert_eq!(*lock.get_mut_or_insert_with(|| unreachable!()), 2);
+    assert!(lock.is_some_unlocked());
+    assert_eq!(lock.into_inner(), Some(2));
+
+    let mut lock = OptionLock::<String>::empty();
+    lock.get_mut_or_insert_with(String::new).push('a');
+    assert_eq!(lock.try_take(), Ok(String::from("a")));
+}
+
+#[test]
+fn result_aliases() {
+    fn lock_value(lock: &OptionLock<u32>) -> TryLockResult<'_, u32> {
+        lock.try_lock()
+    }
+    fn get_value(lock: &OptionLock<u32>) -> TryGetResult<'_, u32> {
+        lock.try_get()
+    }
+    fn lock_mutex(mutex: &Mutex<u32>) -> MutexLockResult<'_, u32> {
+        mutex.try_lock()
+    }
+
+    let lock = OptionLock::new(1);
+    assert_eq!(lock_value(&lock).unwrap().as_ref(), Some(&1));
+    assert_eq!(*get_value(&lock).unwrap(), 1);
+    let mutex = Mutex::new(2);
+    let guard = lock_mutex(&mutex).unwrap();
+    assert_eq!(lock_mutex(&mutex).unwrap_err(), MutexLockError::Unavailable);
+    assert_eq!(*guard, 2);
+}
+
+#[test]
+fn option_guard_drop_value() {
+    let lock = OptionLock::new(Arc::new(()));
+    let rc = lock.try_lock().unwrap().as_ref().cloned().unwrap();
+    assert_eq!(Arc::strong_count(&rc), 2);
+    let mut guard = lock.try_lock().unwrap();
+    guard.drop_value();
+    assert!(guard.is_none());
+    assert_eq!(Arc::strong_count(&rc), 1);
+    guard.drop_value();
+    assert_eq!(guard.clear(), None);
+    guard.replace(rc);
+    assert!(guard.clear().is_some());
+    drop(guard);
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn panicking_drop() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct PanicDrop;
+
+    impl Drop for PanicDrop {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+            panic!("expected");
+        }
+    }
+
+    let lock = OptionLock::new(PanicDrop);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        lock.try_lock().unwrap().drop_value();
+    }));
+    assert!(res.is_err());
+    assert!(lock.is_none_unlocked());
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 1);
+
+    lock.try_fill(PanicDrop).ok().unwrap();
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = lock.try_lock().unwrap();
+        guard.replace(PanicDrop);
+    }));
+    assert!(res.is_err());
+    assert!(lock.is_some_unlocked());
+    assert_eq!(DROPPED.load(Ordering::Relaxed), 2);
+    std::mem::forget(lock);
+}
+
+#[test]
+fn lazy_clone_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static PANIC: AtomicBool = AtomicBool::new(true);
+
+    struct PanicClone(i32);
+
+    impl Clone for PanicClone {
+        fn clone(&self) -> Self {
+            if PANIC.load(Ordering::Relaxed) {
+                panic!("expected");
+            }
+            Self(self.0)
+        }
+    }
+
+    let captured = PanicClone(5);
+    let lazy = Lazy::new(move || captured.0);
+    let res = catch_unwind(AssertUnwindSafe(|| lazy.clone()));
+    assert!(res.is_err());
+    PANIC.store(false, Ordering::Relaxed);
+    let cloned = lazy.clone();
+    assert_eq!(*lazy, 5);
+    assert_eq!(*cloned, 5);
+}
+
+#[test]
+fn lazy_force_during_clone() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static CLONING: AtomicBool = AtomicBool::new(false);
+
+    struct SlowClone(i32);
+
+    impl Clone for SlowClone {
+        fn clone(&self) -> Self {
+            CLONING.store(true, Ordering::Release);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            Self(self.0)
+        }
+    }
+
+    let captured = SlowClone(5);
+    let lazy = Arc::new(Lazy::new(move || captured.0));
+    let cloner = {
+        let lazy = lazy.clone();
+        std::thread::spawn(move || drop((*lazy).clone()))
+    };
+    while !CLONING.load(Ordering::Acquire) {
+        std::thread::yield_now();
+    }
+    // the waiting thread is parked until the clone releases the lock
+    assert_eq!(**lazy, 5);
+    cloner.join().unwrap();
+}
+
+#[test]
+fn arc_lock_ptr() {
+    let a = Arc::new(OptionLock::from(1));
+    let b = Arc::new(OptionLock::from(1));
+    assert!(a.ptr_eq(&a.clone()));
+    assert!(!a.ptr_eq(&b));
+
+    let guard = a.try_lock_arc().unwrap();
+    assert_eq!(guard.lock_ptr(), Arc::as_ptr(&a));
+    assert_ne!(guard.lock_ptr(), Arc::as_ptr(&b));
+    drop(guard);
+    let guard = b.try_get_arc().unwrap();
+    assert_eq!(guard.lock_ptr(), Arc::as_ptr(&b));
+
+    let m = Arc::new(Mutex::new(1));
+    let guard = m.try_lock_arc().unwrap();
+    assert_eq!(guard.lock_ptr() as *const Mutex<i32>, Arc::as_ptr(&m));
+}
+
+#[test]
+fn option_lock_spin_fill() {
+    let lock = Arc::new(OptionLock::new(0));
+    let producer = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            lock.spin_fill(1);
+            lock.spin_fill_with(|| 2);
+        })
+    };
+    assert_eq!(lock.spin_take(), 0);
+    assert_eq!(lock.spin_take(), 1);
+    assert_eq!(lock.spin_take(), 2);
+    producer.join().unwrap();
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn mutex_poison_on_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let m = Mutex::new(1);
+    let res = catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = m.try_lock().unwrap();
+        *guard += 1;
+        panic!("expected");
+    }));
+    assert!(res.is_err());
+    assert!(!m.is_locked());
+    // the value is only dropped on a panic when `std` is enabled
+    if cfg!(any(feature = "no_poison", not(feature = "std"))) {
+        assert_eq!(m.try_copy(), Ok(2));
+    } else {
+        assert!(m.is_poisoned());
+        assert_eq!(m.try_copy(), Err(MutexLockError::Poisoned));
+    }
+}
+
+#[test]
+fn option_guard_into_inner() {
+    let lock = OptionLock::new(1);
+    assert_eq!(lock.try_lock().unwrap().into_inner(), Some(1));
+    assert!(lock.is_none_unlocked());
+    assert_eq!(lock.try_lock().unwrap().into_inner(), None);
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn option_lock_pinned_future() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_raw() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            noop_raw()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(std::ptr::null(), &VTABLE)
+    }
+    let waker = unsafe { Waker::from_raw(noop_raw()) };
+    let mut cx = Context::from_waker(&waker);
+
+    let lock: OptionLock<Pin<Box<dyn Future<Output = u32>>>> =
+        OptionLock::new(Box::pin(async { 5 }));
+    let mut guard = lock.try_get().unwrap();
+    assert!(lock.is_locked());
+    assert_eq!((*guard).as_mut().poll(&mut cx), Poll::Ready(5));
+    drop(guard);
+    assert!(!lock.is_locked());
+
+    // a future which is not `Unpin` is polled in place
+    let lock = OptionLock::new(async {
+        let value = 6;
+        let value_ref = &value;
+        std::future::ready(()).await;
+        *value_ref
+    });
+    // SAFETY: the future is not moved out of the lock
+    let mut guard = unsafe { lock.try_get_pinned() }.unwrap();
+    assert!(lock.is_locked());
+    assert_eq!(guard.as_mut().poll(&mut cx), Poll::Ready(6));
+    drop(guard);
+    assert!(!lock.is_locked());
+}
+
+#[test]
+fn option_lock_try_replace_keep() {
+    let lock = OptionLock::<u32>::empty();
+    let (prev, mut guard) = lock.try_replace_keep(1).unwrap();
+    assert_eq!(prev, None);
+    *guard += 1;
+    assert_eq!(
+        lock.try_replace_keep(5).unwrap_err(),
+        (5, OptionLockError::Unavailable)
+    );
+    drop(guard);
+    let (prev, guard) = lock.try_replace_keep(3).unwrap();
+    assert_eq!(prev, Some(2));
+    assert_eq!(*guard, 3);
+}
+
+#[test]
+fn option_lock_new_array() {
+    let slots: [OptionLock<usize>; 4] =
+        OptionLock::new_array(|idx| if idx % 2 == 0 { Some(idx) } else { None });
+    assert_eq!(slots[0].try_take(), Ok(0));
+    assert!(slots[1].is_none_unlocked());
+    assert_eq!(slots[2].try_take(), Ok(2));
+    assert!(slots[3].is_none_unlocked());
+
+    // the slots already created are dropped if the function panics
+    let value = Arc::new(());
+    let result = std::panic::catch_unwind(|| {
+        OptionLock::<Arc<()>>::new_array::<4>(|idx| {
+            if idx == 2 {
+                panic!("init failed");
+            }
+            Some(value.clone())
+        })
+    });
+    assert!(result.is_err());
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn option_guard_replace_if() {
+    let lock = OptionLock::<u32>::empty();
+    let mut guard = lock.try_lock().unwrap();
+    assert_eq!(guard.replace_if(1, |v| v.is_some()), Err(1));
+    assert_eq!(guard.replace_if(1, |v| v.is_none()), Ok(None));
+    assert_eq!(guard.replace_if(2, |v| v == Some(&5)), Err(2));
+    assert_eq!(guard.replace_if(2, |v| v == Some(&1)), Ok(Some(1)));
+    assert_eq!(guard.as_ref(), Some(&2));
+}
+
+#[test]
+fn option_lock_debug_value() {
+    let lock = OptionLock::<i32>::empty();
+    assert_eq!(format!("{:?}", lock.debug_value()), "OptionLock(None)");
+    lock.try_fill(1).unwrap();
+    assert_eq!(format!("{:?}", lock.debug_value()), "OptionLock(Some(1))");
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(format!("{:?}", lock.debug_value()), "OptionLock(Locked)");
+    drop(guard);
+    assert!(!lock.is_locked());
+}
+
+#[test]
+fn once_cell_get_or_try_init_once() {
+    let cell = OnceCell::<u32>::empty();
+    assert_eq!(
+        cell.get_or_try_init_once(|| Err("failed")),
+        Err(InitError::Failed("failed"))
+    );
+    assert!(cell.is_poisoned());
+    assert_eq!(
+        cell.get_or_try_init_once(|| -> Result<u32, &str> { unreachable!() }),
+        Err(InitError::Poisoned)
+    );
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.set(1), Err(1));
+    assert!(!cell.is_locked());
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cell.get_or_init(|| 1);
+    }));
+    assert!(res.is_err());
+
+    let cell = OnceCell::<u32>::empty();
+    assert_eq!(cell.get_or_try_init_once(|| Ok::<_, ()>(5)), Ok(&5));
+    assert_eq!(cell.get_or_try_init_once(|| Err(())), Ok(&5));
+    assert!(!cell.is_poisoned());
+}
+
+/// Wait in `get_or_try_init_once` while another thread's initializer runs and
+/// then releases the cell without a value, by panicking or returning an error.
+fn once_cell_retry_after_release(panics: bool) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let cell = Arc::new(OnceCell::<u32>::empty());
+    let started = Arc::new(AtomicBool::new(false));
+    let other = {
+        let cell = cell.clone();
+        let started = started.clone();
+        std::thread::spawn(move || {
+            cell.get_or_try_init(|| {
+                started.store(true, Ordering::Release);
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                if panics {
+                    panic!("init failed");
+                }
+                Err(())
+            })
+            .map(|_| ())
+        })
+    };
+    while !started.load(Ordering::Acquire) {
+        std::thread::yield_now();
+    }
+    assert_eq!(cell.get_or_try_init_once(|| Ok::<_, ()>(5)), Ok(&5));
+    assert!(!cell.is_poisoned());
+    match other.join() {
+        Ok(result) => assert!(!panics && result.is_err()),
+        Err(_) => assert!(panics),
+    }
+}
+
+#[test]
+fn once_cell_get_or_try_init_once_after_panic() {
+    once_cell_retry_after_release(true);
+}
+
+#[test]
+fn once_cell_get_or_try_init_once_after_error() {
+    once_cell_retry_after_release(false);
+}
+
+#[test]
+fn arc_mutex_guard_map() {
+    let m = Arc::new(Mutex::new((1, String::from("a"))));
+    let mut guard = m.try_lock_arc().unwrap().map(|v| &mut v.1);
+    guard.push('b');
+    assert_eq!(format!("{:?}", &guard), "MappedMutexGuardArc(\"ab\")");
+    assert!(m.is_locked());
+    drop(guard);
+    assert!(!m.is_locked());
+    assert_eq!(m.try_clone(), Ok((1, String::from("ab"))));
+}
+
+#[cfg(feature = "jitter")]
+#[test]
+fn option_lock_try_lock_backoff() {
+    let lock = OptionLock::new(1);
+    let guard = lock.try_lock_backoff(1).unwrap();
+    assert_eq!(
+        lock.try_lock_backoff(10).unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    assert_eq!(
+        lock.try_lock_backoff_with(0, &mut XorShiftRng::new(0))
+            .unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    drop(guard);
+
+    let lock = Arc::new(lock);
+    let waiter = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            let mut rng = XorShiftRng::new(1);
+            loop {
+                if let Ok(mut guard) = lock.try_lock_backoff_with(16, &mut rng) {
+                    if guard.as_ref() == Some(&2) {
+                        break guard.take();
+                    }
+                }
+            }
+        })
+    };
+    lock.spin_lock().replace(2);
+    assert_eq!(waiter.join().unwrap(), Some(2));
+}
+
+#[test]
+fn option_lock_try_empty() {
+    let lock = OptionLock::new(1);
+    assert_eq!(lock.try_empty(), Ok(Some(1)));
+    assert_eq!(lock.try_empty(), Ok(None));
+    assert!(lock.is_none_unlocked());
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock.try_empty(), Err(OptionLockError::Unavailable));
+    drop(guard);
+}
+
+#[cfg(feature = "async")]
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut fut = Box::pin(fut);
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(result) = fut.as_mut().poll(&mut cx) {
+            break result;
+        }
+        std::thread::park();
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn once_cell_wait_async() {
+    let cell = Arc::new(OnceCell::<u32>::empty());
+    let setter = {
+        let cell = cell.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            cell.set(5).unwrap();
+        })
+    };
+    assert_eq!(block_on(cell.wait_async()), &5);
+    assert_eq!(block_on(async { *(&*cell).await }), 5);
+    setter.join().unwrap();
+
+    // a task which stops waiting does not affect the cell
+    let cell = OnceCell::<u32>::empty();
+    {
+        use std::future::Future;
+        use std::task::{Context, Poll, Wake, Waker};
+
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut wait = Box::pin(cell.wait_async());
+        assert_eq!(
+            wait.as_mut().poll(&mut Context::from_waker(&waker)),
+            Poll::Pending
+        );
+    }
+    assert!(!cell.is_locked());
+    assert_eq!(cell.get_or_init(|| 6), &6);
+    assert_eq!(block_on(cell.wait_async()), &6);
+}
+
+#[test]
+fn option_lock_get_unchecked() {
+    let lock = OptionLock::new(1);
+    unsafe {
+        *lock.get_mut_unchecked() += 1;
+        assert_eq!(*lock.get_unchecked(), 2);
+    }
+    assert_eq!(lock.try_take(), Ok(2));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "empty OptionLock")]
+fn option_lock_get_unchecked_empty() {
+    let lock = OptionLock::<u32>::empty();
+    unsafe {
+        lock.get_unchecked();
+    }
+}
+
+#[test]
+fn option_lock_try_lock_state() {
+    let lock = OptionLock::<u32>::empty();
+    let (mut guard, is_some) = lock.try_lock_state().unwrap();
+    assert!(!is_some);
+    assert_eq!(
+        lock.try_lock_state().unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    guard.replace(1);
+    drop(guard);
+    let (guard, is_some) = lock.try_lock_state().unwrap();
+    assert!(is_some);
+    assert_eq!(guard.as_ref(), Some(&1));
+}
+
+#[test]
+fn lazy_force_mut() {
+    let mut lazy = Lazy::new(|| vec![1]);
+    assert!(!Lazy::is_initialized(&lazy));
+    Lazy::force_mut(&mut lazy).push(2);
+    assert!(Lazy::is_initialized(&lazy));
+    Lazy::force_mut(&mut lazy).push(3);
+    assert_eq!(*lazy, vec![1, 2, 3]);
+}
+
+#[test]
+fn option_lock_split() {
+    let lock = OptionLock::empty();
+    let (filler, taker) = lock.split();
+    assert_eq!(taker.try_take(), Err(OptionLockError::FillState));
+    filler.try_fill(1).unwrap();
+    assert_eq!(filler.try_fill(2), Err(2));
+    assert_eq!(format!("{:?}", &taker), "Taker(OptionLock(Some))");
+    assert_eq!(taker.try_take(), Ok(1));
+
+    let lock = Arc::new(OptionLock::empty());
+    let (filler, taker) = lock.split_arc();
+    let producer = std::thread::spawn(move || {
+        for val in 0..10 {
+            filler.spin_fill(val);
+        }
+    });
+    let total: u32 = (0..10).map(|_| taker.spin_take()).sum();
+    producer.join().unwrap();
+    assert_eq!(total, 45);
+}
+
+#[test]
+fn box_option_lock_swap() {
+    let lock = BoxOptionLock::empty();
+    assert_eq!(lock.try_swap(Box::new([1u8; 1024])), Ok(None));
+    let prev = lock.try_swap(Box::new([2u8; 1024])).unwrap().unwrap();
+    assert_eq!(prev[0], 1);
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(format!("{:?}", &lock), "BoxOptionLock(Locked)");
+    assert_eq!(lock.try_swap(Box::new([3u8; 1024])).unwrap_err()[0], 3);
+    drop(guard);
+    assert_eq!(lock.into_inner().unwrap()[0], 2);
+}
+
+#[test]
+fn option_guard_eq() {
+    let a = OptionLock::new(2);
+    let b = OptionLock::new(2);
+    let c = OptionLock::empty();
+    let ga = a.try_lock().unwrap();
+    let gb = b.try_lock().unwrap();
+    let gc = c.try_lock().unwrap();
+    assert_eq!(ga, Some(2));
+    assert_ne!(ga, Some(3));
+    assert_eq!(gc, None);
+    assert_eq!(ga, gb);
+    assert_ne!(ga, gc);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn option_lock_yield() {
+    let lock = Arc::new(OptionLock::empty());
+    let consumer = {
+        let lock = lock.clone();
+        std::thread::spawn(move || (0..10).map(|_| lock.take_yield()).sum::<u32>())
+    };
+    for val in 0..10 {
+        lock.fill_yield(val);
+    }
+    assert_eq!(consumer.join().unwrap(), 45);
+}
+
+#[test]
+fn option_lock_transfer() {
+    let a = OptionLock::new(1);
+    let b = OptionLock::empty();
+    assert_eq!(OptionLock::transfer(&a, &a), Ok(false));
+    assert_eq!(OptionLock::transfer(&b, &a), Ok(false));
+    assert_eq!(OptionLock::transfer(&a, &b), Ok(true));
+    assert!(a.is_none_unlocked());
+    assert!(b.is_some_unlocked());
+    let guard = a.try_lock().unwrap();
+    assert_eq!(
+        OptionLock::transfer(&b, &a),
+        Err(OptionLockError::Unavailable)
+    );
+    drop(guard);
+    assert!(b.is_some_unlocked());
+
+    // transfers in opposite directions must all complete
+    let locks = Arc::new((OptionLock::new(1), OptionLock::empty()));
+    let threads: Vec<_> = (0..2)
+        .map(|dir| {
+            let locks = locks.clone();
+            std::thread::spawn(move || {
+                let mut moved = 0;
+                while moved < 100 {
+                    let res = if dir == 0 {
+                        OptionLock::transfer(&locks.0, &locks.1)
+                    } else {
+                        OptionLock::transfer(&locks.1, &locks.0)
+                    };
+                    if res == Ok(true) {
+                        moved += 1;
+                    } else {
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(locks.0.try_take(), Ok(1));
+    assert!(locks.1.is_none_unlocked());
+}
+
+#[test]
+fn owned_map() {
+    let lock = OptionLock::new(5u32).map(|v| v.to_string());
+    assert_eq!(lock.into_inner(), Some("5".to_string()));
+    let lock = OptionLock::<u32>::empty().map(|v| v + 1);
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn option_lock_try_get_or_try_init() {
+    let lock = OptionLock::<u32>::empty();
+    assert_eq!(
+        lock.try_get_or_try_init(|| Err("failed")).unwrap_err(),
+        "failed"
+    );
+    assert!(lock.is_none_unlocked());
+    {
+        let mut guard = lock
+            .try_get_or_try_init(|| Ok::<_, ()>(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(*guard, 1);
+        *guard += 1;
+        assert_eq!(
+            lock.try_get_or_try_init(|| Ok::<_, ()>(5))
+                .unwrap()
+                .unwrap_err(),
+            OptionLockError::Unavailable
+        );
+    }
+    let guard = lock
+        .try_get_or_try_init(|| -> Result<u32, ()> { panic!("not called") })
+        .unwrap()
+        .unwrap();
+    assert_eq!(*guard, 2);
+}
+
+#[test]
+fn once_cell_get_mut_or_init() {
+    let mut cell = OnceCell::empty();
+    *cell.get_mut_or_init(|| 1) += 1;
+    assert_eq!(*cell.get_mut_or_init(|| 5), 2);
+    assert_eq!(cell.get(), Some(&2));
+}
+
+#[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+#[test]
+fn option_lock_reentrant_spin() {
+    let lock = OptionLock::new(1);
+    let guard = lock.try_lock().unwrap();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        lock.spin_lock();
+    }));
+    assert!(res.is_err());
+    drop(guard);
+    // the owner is cleared when the guard is released
+    assert_eq!(*lock.spin_get(), 1);
+
+    // owned guards are not tracked
+    let lock = Arc::new(OptionLock::new(1));
+    let guard = lock.try_lock_arc().unwrap();
+    let handle = std::thread::spawn(move || drop(guard));
+    assert_eq!(*lock.spin_get(), 1);
+    handle.join().unwrap();
+}
+
+#[test]
+fn option_lock_guard_handoff() {
+    let lock = OptionLock::new(1);
+    let guard = lock.try_lock().unwrap();
+
+    #[cfg(not(all(feature = "debug_reentrancy", debug_assertions)))]
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            drop(guard);
+        });
+        // the guard is held by another thread, so this waits for its release
+        assert_eq!(*lock.spin_get(), 1);
+    });
+
+    // borrowed guards cannot be handed off to another thread, so the thread
+    // recorded as the owner of the lock is always the one holding the guard
+    #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+    {
+        trait AmbiguousIfSend<A> {
+            fn check() {}
+        }
+        impl<T: ?Sized> AmbiguousIfSend<()> for T {}
+        impl<T: ?Sized + Send> AmbiguousIfSend<u8> for T {}
+        <OptionGuard<'_, i32> as AmbiguousIfSend<_>>::check();
+        <MutexGuard<'_, i32> as AmbiguousIfSend<_>>::check();
+        drop(guard);
+        assert_eq!(*lock.spin_get(), 1);
+    }
+}
+
+#[test]
+fn option_lock_try_exchange() {
+    let lock = OptionLock::<u32>::empty();
+    assert_eq!(lock.try_exchange(1), Ok(None));
+    assert_eq!(lock.try_exchange(2), Ok(Some(1)));
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock.try_exchange(3), Err(3));
+    drop(guard);
+    assert_eq!(lock.try_take(), Ok(2));
+}
+
+#[test]
+fn option_lock_zero_sized() {
+    #[cfg(not(any(
+        all(feature = "debug_reentrancy", debug_assertions),
+        feature = "ticket",
+        feature = "track_hold_time",
+        feature = "instrument"
+    )))]
+    assert_eq!(
+        std::mem::size_of::<OptionLock<()>>(),
+        std::mem::size_of::<std::sync::atomic::AtomicU8>()
+    );
+    let lock = OptionLock::<()>::empty();
+    assert_eq!(lock.try_fill(()), Ok(()));
+    assert_eq!(lock.try_fill(()), Err(()));
+    assert_eq!(lock.try_take(), Ok(()));
+    assert_eq!(lock.try_take(), Err(OptionLockError::FillState));
+}
+
+#[test]
+fn owned_reset() {
+    let value = Arc::new(());
+    let mut lock = OptionLock::new(value.clone());
+    assert_eq!(Arc::strong_count(&value), 2);
+    lock.reset();
+    assert_eq!(Arc::strong_count(&value), 1);
+    assert!(lock.is_none_unlocked());
+    lock.reset();
+    assert_eq!(lock.try_fill(value.clone()), Ok(()));
+    drop(lock);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn option_lock_try_get_or_insert() {
+    let lock = OptionLock::<u32>::empty();
+    {
+        let mut guard = lock.try_get_or_insert(1).unwrap();
+        *guard += 1;
+        assert_eq!(
+            lock.try_get_or_insert(5).unwrap_err(),
+            OptionLockError::Unavailable
+        );
+    }
+    assert_eq!(*lock.try_get_or_insert(5).unwrap(), 2);
+    assert_eq!(lock.try_take(), Ok(2));
+    assert_eq!(*lock.try_get_or_default().unwrap(), 0);
+    assert_eq!(lock.try_take(), Ok(0));
+}
+
+#[test]
+fn option_lock_raw_acquire() {
+    let lock = OptionLock::new(1);
+    unsafe {
+        assert_eq!(lock.raw_try_acquire(), Some(true));
+        assert!(lock.is_locked());
+        assert_eq!(lock.raw_try_acquire(), None);
+        *lock.get_mut_unchecked() += 1;
+        lock.raw_release(true);
+    }
+    assert!(!lock.is_locked());
+    assert_eq!(lock.try_take(), Ok(2));
+    unsafe {
+        assert_eq!(lock.raw_try_acquire(), Some(false));
+        lock.raw_release(false);
+    }
+    assert!(lock.is_none_unlocked());
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn option_lock_poll_fill() {
+    use std::future::poll_fn;
+
+    let lock = Arc::new(OptionLock::new(1u32));
+    let taker = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            let first = block_on(poll_fn(|cx| lock.poll_take(cx)));
+            let second = block_on(poll_fn(|cx| lock.poll_take(cx)));
+            (first, second)
+        })
+    };
+    let mut value = Some(2);
+    block_on(poll_fn(|cx| lock.poll_fill(cx, &mut value)));
+    assert_eq!(value, None);
+    assert_eq!(taker.join().unwrap(), (1, 2));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn channel_oneshot() {
+    use option_lock::channel::oneshot;
+
+    let (sender, receiver) = oneshot();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    let handle = std::thread::spawn(move || sender.send(5));
+    assert_eq!(receiver.recv(), Ok(5));
+    assert_eq!(handle.join().unwrap(), Ok(()));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+
+    let (sender, receiver) = oneshot::<u32>();
+    drop(sender);
+    assert_eq!(receiver.recv(), Err(RecvError));
+
+    let (sender, receiver) = oneshot();
+    assert!(!sender.is_closed());
+    drop(receiver);
+    assert!(sender.is_closed());
+    assert_eq!(sender.send(5), Err(5));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn channel_recv_async() {
+    let (sender, receiver) = option_lock::channel::oneshot();
+    let handle = std::thread::spawn(move || sender.send(5));
+    assert_eq!(block_on(receiver.recv_async()), Ok(5));
+    assert_eq!(handle.join().unwrap(), Ok(()));
+}
+
+#[test]
+fn option_lock_is_some_unlocked_acquire() {
+    let lock = Arc::new(OptionLock::<String>::empty());
+    assert!(!lock.is_some_unlocked_acquire());
+    let filler = {
+        let lock = lock.clone();
+        std::thread::spawn(move || lock.try_fill("value".to_string()).unwrap())
+    };
+    while !lock.is_some_unlocked_acquire() {
+        std::thread::yield_now();
+    }
+    assert_eq!(unsafe { lock.get_unchecked() }, "value");
+    filler.join().unwrap();
+    let guard = lock.try_lock().unwrap();
+    assert!(!lock.is_some_unlocked_acquire());
+    drop(guard);
+}
+
+#[test]
+fn option_guard_spin_relock() {
+    let lock = Arc::new(OptionLock::new(0u32));
+    let other = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            for _ in 0..100 {
+                let mut guard = lock.spin_lock();
+                *guard.as_mut().unwrap() += 1;
+            }
+        })
+    };
+    let mut guard = lock.spin_lock();
+    for _ in 0..100 {
+        *guard.as_mut().unwrap() += 1;
+        guard = guard.spin_relock();
+    }
+    drop(guard);
+    other.join().unwrap();
+    assert_eq!(lock.try_take(), Ok(200));
+}
+
+#[test]
+fn option_lock_state_name() {
+    let lock = OptionLock::<i32>::empty();
+    assert_eq!(lock.state_name(), "None");
+    lock.try_fill(1).unwrap();
+    assert_eq!(lock.state_name(), "Some");
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock.state_name(), "Locked");
+    drop(guard);
+    assert_eq!(
+        format!("OptionLock({})", lock.state_name()),
+        format!("{:?}", lock)
+    );
+}
+
+#[test]
+fn mutex_lock_error_from() {
+    fn lock_and_take(lock: &OptionLock<u32>) -> Result<u32, MutexLockError> {
+        Ok(lock.try_take()?)
+    }
+    fn into_inner(mutex: Mutex<u32>) -> Result<u32, MutexLockError> {
+        Ok(mutex.into_inner()?)
+    }
+    let lock = OptionLock::new(1);
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock_and_take(&lock), Err(MutexLockError::Unavailable));
+    drop(guard);
+    assert_eq!(lock_and_take(&lock), Ok(1));
+    assert_eq!(lock_and_take(&lock), Err(MutexLockError::Poisoned));
+    assert_eq!(into_inner(Mutex::new(2)), Ok(2));
+    assert_eq!(MutexLockError::from(PoisonError), MutexLockError::Poisoned);
+}
+
+#[test]
+fn option_lock_try_lock_weak() {
+    let lock = OptionLock::new(1);
+    let guard = loop {
+        if let Ok(guard) = lock.try_lock_weak() {
+            break guard;
+        }
+    };
+    assert_eq!(guard.as_ref(), Some(&1));
+    assert_eq!(
+        lock.try_lock_weak().unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    drop(guard);
+    assert_eq!(lock.try_take(), Ok(1));
+}
+
+#[test]
+fn option_lock_peek_copy() {
+    let lock = OptionLock::<u32>::empty();
+    unsafe {
+        assert_eq!(lock.peek_copy(), None);
+        lock.try_fill(5).unwrap();
+        assert_eq!(lock.peek_copy(), Some(5));
+        let guard = lock.try_lock().unwrap();
+        assert_eq!(lock.peek_copy(), None);
+        drop(guard);
+    }
+    assert_eq!(lock.try_take(), Ok(5));
+}
+
+// reentrancy is only detected when `std` is enabled
+#[cfg(feature = "std")]
+#[test]
+fn once_cell_reentrant_init() {
+    let cell = OnceCell::<u32>::empty();
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cell.get_or_init(|| *cell.get_or_init(|| 1) + 1);
+    }));
+    let err = res.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<&str>(),
+        Some(&"reentrant initialization of OnceCell")
+    );
+    assert!(!cell.is_locked());
+    // initialization of a different cell within the initializer is permitted
+    let other = OnceCell::<u32>::empty();
+    assert_eq!(cell.get_or_init(|| *other.get_or_init(|| 2) + 1), &3);
+}
+
+#[test]
+fn mutex_try_lock_static() {
+    static MUTEX: Mutex<u32> = Mutex::new(1);
+    let mut guard: StaticMutexGuard<u32> = MUTEX.try_lock_static().unwrap();
+    assert_eq!(
+        MUTEX.try_lock_static().unwrap_err(),
+        MutexLockError::Unavailable
+    );
+    *guard += 1;
+    // borrowed guards are tied to the current thread by `debug_reentrancy`
+    #[cfg(not(all(feature = "debug_reentrancy", debug_assertions)))]
+    assert_eq!(std::thread::spawn(move || *guard).join().unwrap(), 2);
+    #[cfg(all(feature = "debug_reentrancy", debug_assertions))]
+    drop(guard);
+    assert_eq!(*MUTEX.try_lock_static().unwrap(), 2);
+}
+
+#[cfg(feature = "ticket")]
+#[test]
+fn option_lock_spin_lock_fair() {
+    let lock = Arc::new(OptionLock::new(Vec::new()));
+    let guard = lock.spin_lock_fair();
+    assert_eq!(lock.fair_waiters(), 0);
+    let handles = (0..4)
+        .map(|idx| {
+            let handle = {
+                let lock = lock.clone();
+                std::thread::spawn(move || {
+                    lock.spin_lock_fair().as_mut().unwrap().push(idx);
+                })
+            };
+            // wait for the thread to take its ticket before starting the next
+            while lock.fair_waiters() != idx + 1 {
+                std::thread::yield_now();
+            }
+            handle
+        })
+        .collect::<Vec<_>>();
+    drop(guard);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(lock.try_take().unwrap(), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn option_lock_try_get_both() {
+    let a = OptionLock::new(1u32);
+    let b = OptionLock::new("b".to_string());
+    {
+        let (mut a_guard, b_guard) = OptionLock::try_get_both(&a, &b).unwrap();
+        *a_guard += b_guard.len() as u32;
+        assert_eq!(
+            OptionLock::try_get_both(&a, &b).unwrap_err(),
+            OptionLockError::Unavailable
+        );
+    }
+    assert_eq!(a.try_take(), Ok(2));
+    // neither lock is held after a failure
+    assert_eq!(
+        OptionLock::try_get_both(&a, &b).unwrap_err(),
+        OptionLockError::FillState
+    );
+    assert!(!b.is_locked());
+    let c = OptionLock::new(3u32);
+    assert_eq!(
+        OptionLock::try_get_both(&c, &c).unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    assert!(!c.is_locked());
+}
+
+#[test]
+fn option_lock_fill_from_indexed() {
+    let slots = OptionLock::<u32>::new_array::<4>(|_| None);
+    assert_eq!(
+        OptionLock::fill_from_indexed(&slots, vec![(2, 20), (0, 0)]),
+        Ok(2)
+    );
+    assert_eq!(
+        OptionLock::fill_from_indexed(&slots, vec![(1, 10), (2, 21), (3, 30)]),
+        Err((2, 21))
+    );
+    assert!(slots[3].is_none_unlocked());
+    let values = OptionLock::drain_slice(&slots).collect::<Vec<_>>();
+    assert_eq!(values, vec![0, 10, 20]);
+}
+
+#[test]
+fn option_lock_lease() {
+    let lock = OptionLock::new(vec![1]);
+    let len = lock
+        .lease(|v| {
+            assert!(lock.is_locked());
+            // the slot cannot be refilled while the value is leased
+            assert_eq!(lock.try_fill(vec![9]), Err(vec![9]));
+            v.push(2);
+            v.len()
+        })
+        .unwrap();
+    assert_eq!(len, 2);
+    let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        lock.lease(|v| {
+            v.push(3);
+            panic!("lease failed");
+        })
+    }));
+    assert!(res.is_err());
+    assert_eq!(lock.try_take(), Ok(vec![1, 2, 3]));
+    assert_eq!(lock.lease(|v| v.len()), Err(OptionLockError::FillState));
+}
+
+#[test]
+fn option_lock_try_fill_with_ref() {
+    let lock = OptionLock::<u32>::empty();
+    {
+        let guard = lock.try_fill_with_ref(|| 5).unwrap();
+        assert_eq!(*guard, 5);
+        assert_eq!(
+            lock.try_fill_with_ref(|| 6).unwrap_err(),
+            OptionLockError::Unavailable
+        );
+    }
+    assert_eq!(
+        lock.try_fill_with_ref(|| panic!("not called")).unwrap_err(),
+        OptionLockError::FillState
+    );
+    assert_eq!(lock.try_take(), Ok(5));
+}
+
+#[test]
+fn option_lock_snapshot_restore() {
+    let lock = OptionLock::new("value".to_string());
+    let snapshot = lock.snapshot();
+    assert_eq!(snapshot.as_deref(), Some("value"));
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock.snapshot(), None);
+    drop(guard);
+    let restored = OptionLock::restore(snapshot);
+    assert_eq!(restored.into_inner().as_deref(), Some("value"));
+    assert!(OptionLock::<u32>::restore(None).is_none_unlocked());
+}
+
+#[test]
+fn arc_guard_pointer_fmt() {
+    let lock = Arc::new(OptionLock::new(1));
+    let guard = lock.try_lock_arc().unwrap();
+    assert_eq!(format!("{:p}", guard), format!("{:p}", Arc::as_ptr(&lock)));
+    drop(guard);
+    let mutex = Arc::new(Mutex::new(1));
+    let guard = mutex.try_lock_arc().unwrap();
+    assert_eq!(format!("{:p}", guard), format!("{:p}", Arc::as_ptr(&mutex)));
+}
+
+#[test]
+fn option_lock_try_clone_arc() {
+    let value = Arc::new(5);
+    let lock = OptionLock::new(value.clone());
+    let cloned = lock.try_clone().unwrap();
+    assert!(Arc::ptr_eq(&cloned, &value));
+    assert_eq!(Arc::strong_count(&value), 3);
+
+    let mut out = None;
+    assert!(lock.try_clone_into(&mut out));
+    assert!(Arc::ptr_eq(out.as_ref().unwrap(), &value));
+    assert!(lock.try_clone_into(&mut out));
+    assert_eq!(Arc::strong_count(&value), 4);
+    let guard = lock.try_lock().unwrap();
+    assert!(!lock.try_clone_into(&mut out));
+    drop(guard);
+    assert!(out.is_some());
+}
+
+#[test]
+fn owned_as_guard_mut() {
+    let mut lock = OptionLock::<u32>::empty();
+    {
+        let mut guard = lock.as_guard_mut();
+        assert!(guard.is_none());
+        assert_eq!(guard.replace(1), None);
+        *guard.as_mut().unwrap() += 1;
+        assert_eq!(guard.as_ref(), Some(&2));
+        assert_eq!(format!("{:?}", guard), "OwnedGuard(Some(2))");
+    }
+    assert_eq!(lock.as_guard_mut().take(), Some(2));
+    assert!(lock.is_none_unlocked());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn option_lock_spin_get_timeout() {
+    use std::time::Duration;
+
+    let lock = Arc::new(OptionLock::<u32>::empty());
+    assert_eq!(
+        lock.spin_get_timeout(Duration::from_millis(10))
+            .unwrap_err(),
+        OptionLockError::FillState
+    );
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(
+        lock.spin_get_timeout(Duration::from_millis(10))
+            .unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    drop(guard);
+    let filler = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            lock.try_fill(5).unwrap();
+        })
+    };
+    assert_eq!(*lock.spin_get_timeout(Duration::from_secs(10)).unwrap(), 5);
+    filler.join().unwrap();
+}
+
+#[test]
+fn option_lock_try_init_slot() {
+    let lock = OptionLock::<u32>::empty();
+    match lock.try_init_slot(|| 1) {
+        InitOutcome::Won(mut guard) => {
+            *guard += 1;
+            assert!(matches!(lock.try_init_slot(|| 5), InitOutcome::Contended));
+        }
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+    assert!(matches!(
+        lock.try_init_slot(|| panic!("not called")),
+        InitOutcome::AlreadyFilled
+    ));
+    assert_eq!(lock.try_take(), Ok(2));
+}
+
+#[test]
+fn option_lock_spin_until() {
+    let lock = Arc::new(OptionLock::<u32>::empty());
+    let filler = {
+        let lock = lock.clone();
+        std::thread::spawn(move || lock.try_fill(5).unwrap())
+    };
+    lock.spin_until(|lock| {
+        std::thread::yield_now();
+        lock.is_some_unlocked()
+    });
+    assert_eq!(lock.try_take(), Ok(5));
+    filler.join().unwrap();
+}
+
+#[test]
+fn option_lock_try_get_map() {
+    let lock = OptionLock::new("value".to_string());
+    assert_eq!(lock.try_get_map(String::len), Ok(5));
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(
+        lock.try_get_map(String::len),
+        Err(OptionLockError::Unavailable)
+    );
+    drop(guard);
+    lock.try_take().unwrap();
+    assert_eq!(
+        lock.try_get_map(String::len),
+        Err(OptionLockError::FillState)
+    );
+}
+
+#[test]
+fn option_lock_get_or_fill_ref() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("a", OptionLock::<String>::empty());
+    let lock = &map["a"];
+    let value = unsafe { lock.get_or_fill_ref(|| "value".to_string()) };
+    assert_eq!(value, "value");
+    let value = unsafe { lock.get_or_fill_ref(|| panic!("not called")) };
+    assert_eq!(value, "value");
+    assert_eq!(map.get_mut("a").unwrap().take().as_deref(), Some("value"));
+}
+
+// A stress test for the value published to `OnceCell::get` by another thread.
+// This is not a model check: the lock is built on core atomics rather than
+// loom's, so it only exercises the orderings on the current hardware.
+#[test]
+fn once_cell_get_publishes_value_stress() {
+    for _ in 0..20 {
+        let cell = Arc::new(OnceCell::<Vec<u32>>::empty());
+        let readers = (0..2)
+            .map(|_| {
+                let cell = cell.clone();
+                std::thread::spawn(move || loop {
+                    if let Some(value) = cell.get() {
+                        assert_eq!(value.as_slice(), &[1, 2, 3]);
+                        break;
+                    }
+                    std::thread::yield_now();
+                })
+            })
+            .collect::<Vec<_>>();
+        cell.set(vec![1, 2, 3]).unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+    }
+}
+
+#[test]
+fn arc_get_guard_release() {
+    let lock = Arc::new(OptionLock::new(1));
+    let mut guard = lock.try_get_arc().unwrap();
+    assert_eq!(Arc::strong_count(&lock), 2);
+    assert!(std::ptr::eq(guard.lock_ptr(), Arc::as_ptr(&lock)));
+    *guard += 1;
+    drop(guard);
+    assert_eq!(Arc::strong_count(&lock), 1);
+    assert!(lock.is_some_unlocked());
+
+    // the guard keeps the lock alive and releases it when dropped
+    let guard = lock.try_get_arc().unwrap();
+    let weak = Arc::downgrade(&lock);
+    drop(lock);
+    assert_eq!(*guard, 2);
+    drop(guard);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn option_lock_get_or_init_reporting() {
+    let lock = OptionLock::<u32>::empty();
+    let (value, inserted) = unsafe { lock.get_or_init_reporting(|| 5) };
+    assert_eq!((*value, inserted), (5, true));
+    let (value, inserted) = unsafe { lock.get_or_init_reporting(|| panic!("not called")) };
+    assert_eq!((*value, inserted), (5, false));
+}
+
+#[test]
+fn option_lock_spin_get_if_present() {
+    let lock = OptionLock::<u32>::empty();
+    assert!(lock.spin_get_if_present().is_none());
+    lock.try_fill(5).unwrap();
+    assert_eq!(lock.spin_get_if_present().as_deref(), Some(&5));
+
+    let lock = Arc::new(OptionLock::<u32>::empty());
+    let guard = lock.try_lock().unwrap();
+    let waiter = {
+        let lock = lock.clone();
+        std::thread::spawn(move || lock.spin_get_if_present().map(|guard| *guard))
+    };
+    std::thread::yield_now();
+    drop(guard);
+    assert_eq!(waiter.join().unwrap(), None);
+}
+
+#[test]
+fn lock_guard_generic() {
+    fn reset<T: Default>(mut guard: impl LockGuard<T>) {
+        *guard = T::default();
+    }
+    let mutex = Arc::new(Mutex::new((1u32, 2u32)));
+    reset(mutex.try_lock().unwrap());
+    assert_eq!(mutex.try_copy(), Ok((0, 0)));
+    *mutex.try_lock_arc().unwrap() = (1, 2);
+    reset(mutex.try_lock_arc().unwrap().map(|value| &mut value.1));
+    assert_eq!(mutex.try_copy(), Ok((1, 0)));
+    let mutex = std::rc::Rc::new(Mutex::new(5));
+    reset(mutex.try_lock_rc().unwrap());
+    assert_eq!(mutex.try_copy(), Ok(0));
+}
+
+#[test]
+fn option_lock_try_update_take() {
+    let lock = OptionLock::new(5u32);
+    // keep the larger of the current and offered values
+    let offer = |value: u32| {
+        lock.try_update_take(|prev| match prev {
+            Some(prev) if prev >= value => (Some(prev), false),
+            _ => (Some(value), true),
+        })
+    };
+    assert_eq!(offer(3), Ok(false));
+    assert_eq!(offer(7), Ok(true));
+    assert_eq!(lock.try_copy(), Ok(7));
+    assert_eq!(lock.try_update_take(|prev| (None, prev)), Ok(Some(7)));
+    assert!(lock.is_none_unlocked());
+
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(
+        lock.try_update_take(|prev| (prev, ())),
+        Err(OptionLockError::Unavailable)
+    );
+    drop(guard);
+}
+
+#[test]
+fn option_lock_raw_state() {
+    use std::sync::atomic::Ordering;
+    let lock = OptionLock::new(5u32);
+    let raw = unsafe { lock.raw_state() };
+    assert_eq!(raw.load(Ordering::Relaxed), state::AVAILABLE);
+    assert_eq!(
+        raw.compare_exchange(
+            state::AVAILABLE,
+            state::SOME,
+            Ordering::Acquire,
+            Ordering::Relaxed
+        ),
+        Ok(state::AVAILABLE)
+    );
+    assert!(lock.is_locked());
+    assert_eq!(unsafe { *lock.get_unchecked() }, 5);
+    unsafe { lock.raw_release(true) };
+    assert_eq!(lock.try_take(), Ok(5));
+    assert_eq!(raw.load(Ordering::Relaxed), state::FREE);
+}
+
+#[test]
+fn once_cell_get_or_init_panic() {
+    let cell = OnceCell::<u32>::empty();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cell.get_or_init(|| panic!())
+    }));
+    assert!(result.is_err());
+    assert!(!cell.is_locked());
+    assert_eq!(cell.get(), None);
+    assert_eq!(cell.get_or_init(|| 1), &1);
+
+    // a thread waiting on the failed initializer takes over initialization
+    let cell = Arc::new(OnceCell::<u32>::empty());
+    let (send, recv) = std::sync::mpsc::channel::<()>();
+    let panicking = {
+        let cell = cell.clone();
+        std::thread::spawn(move || {
+            cell.get_or_init(|| {
+                recv.recv().unwrap();
+                panic!("failed initializer")
+            });
+        })
+    };
+    while !cell.is_locked() {
+        std::thread::yield_now();
+    }
+    let waiting = {
+        let cell = cell.clone();
+        std::thread::spawn(move || *cell.get_or_init(|| 2))
+    };
+    std::thread::yield_now();
+    send.send(()).unwrap();
+    assert!(panicking.join().is_err());
+    assert_eq!(waiting.join().unwrap(), 2);
+    assert_eq!(cell.get(), Some(&2));
+}
+
+#[test]
+fn array_lock_slots() {
+    let slots = ArrayLock::<u32, 3>::empty();
+    assert_eq!(slots.try_take_any(), None);
+    assert_eq!(slots.try_fill_next(1), Ok(0));
+    let guard = slots[1].try_lock().unwrap();
+    // a locked slot is skipped
+    assert_eq!(slots.try_fill_next(2), Ok(2));
+    assert_eq!(slots.try_fill_next(3), Err(3));
+    drop(guard);
+    assert_eq!(slots.try_fill_next(3), Ok(1));
+    assert_eq!(slots.try_take_any(), Some((0, 1)));
+    let _guard = slots[1].try_lock().unwrap();
+    assert_eq!(slots.try_take_any(), Some((2, 2)));
+    assert_eq!(slots.try_take_any(), None);
+}
+
+#[test]
+fn option_lock_drain() {
+    let lock = OptionLock::new(1);
+    {
+        let mut drain = lock.drain();
+        assert_eq!(drain.next(), Some(1));
+        assert_eq!(drain.next(), None);
+        lock.try_fill(2).unwrap();
+        assert_eq!(drain.next(), Some(2));
+        lock.try_fill(3).unwrap();
+    }
+    // a value stored after draining is dropped along with the iterator
+    assert!(lock.is_none_unlocked());
+
+    let lock = OptionLock::new(4);
+    let guard = lock.try_lock().unwrap();
+    {
+        // neither advancing nor dropping the iterator waits for the guard
+        let mut drain = lock.drain();
+        assert_eq!(drain.next(), None);
+    }
+    assert!(lock.is_locked());
+    let mut drain = lock.drain();
+    drop(guard);
+    assert_eq!(drain.next(), Some(4));
+    drop(drain);
+
+    // the lock is released before a value with a panicking destructor is dropped
+    struct PanicDrop;
+
+    impl Drop for PanicDrop {
+        fn drop(&mut self) {
+            panic!("drop failed");
+        }
+    }
+
+    let lock = OptionLock::new(PanicDrop);
+    let drain = lock.drain();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(drain)));
+    assert!(result.is_err());
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn mutex_guard_unlocked() {
+    let mutex = Mutex::new(1);
+    let guard = mutex.try_lock().unwrap();
+    let (result, guard) = MutexGuard::unlocked(guard, || {
+        assert!(!mutex.is_locked());
+        *mutex.try_lock().unwrap() += 1;
+        "done"
+    });
+    assert_eq!(result, "done");
+    let guard = guard.unwrap();
+    assert!(mutex.is_locked());
+    assert_eq!(*guard, 2);
+    drop(guard);
+    assert!(!mutex.is_locked());
+
+    // the lock is left unlocked if the function panics
+    let guard = mutex.try_lock().unwrap();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        MutexGuard::unlocked(guard, || panic!())
+    }));
+    assert!(result.is_err());
+    assert!(!mutex.is_locked());
+    assert_eq!(mutex.try_copy(), Ok(2));
+
+    // a value taken while unlocked is reported instead of waited for
+    let guard = mutex.try_lock().unwrap();
+    let (taken, guard) =
+        MutexGuard::unlocked(guard, || MutexGuard::extract(mutex.try_lock().unwrap()));
+    assert_eq!(taken, 2);
+    assert!(guard.is_err());
+    assert!(!mutex.is_locked());
+    assert_eq!(mutex.try_lock().unwrap_err(), MutexLockError::Poisoned);
+}
+
+#[test]
+fn option_guard_into_mutex_guard() {
+    let lock = OptionLock::<u32>::empty();
+    let mut guard = lock.try_lock().unwrap().into_mutex_guard().unwrap_err();
+    assert!(lock.is_locked());
+    guard.replace(5);
+    let mut guard = guard.into_mutex_guard().unwrap();
+    *guard += 1;
+    assert!(lock.is_locked());
+    drop(guard);
+    assert_eq!(lock.try_take(), Ok(6));
+}
+
+#[test]
+fn track_caller_locations() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::Mutex as StdMutex;
+
+    // record the locations of panics raised on this thread
+    static LINES: StdMutex<Vec<u32>> = StdMutex::new(Vec::new());
+    let thread = std::thread::current().id();
+    let prev_hook = Arc::new(std::panic::take_hook());
+    std::panic::set_hook({
+        let prev_hook = prev_hook.clone();
+        Box::new(move |info| {
+            if std::thread::current().id() == thread {
+                LINES.lock().unwrap().push(info.location().unwrap().line());
+            } else {
+                prev_hook(info)
+            }
+        })
+    });
+
+    let lazy = Lazy::<u32>::new(|| panic!("failed"));
+    assert!(catch_unwind(AssertUnwindSafe(|| *lazy)).is_err());
+    let lazy_line = line!() + 1;
+    assert!(catch_unwind(AssertUnwindSafe(|| *lazy)).is_err());
+
+    let mut lazy = Lazy::<u32>::new(|| panic!("failed"));
+    assert!(catch_unwind(AssertUnwindSafe(|| *Lazy::force_mut(&mut lazy))).is_err());
+    let force_mut_line = line!() + 1;
+    assert!(catch_unwind(AssertUnwindSafe(|| *Lazy::force_mut(&mut lazy))).is_err());
+
+    std::panic::set_hook(Box::new(move |info| prev_hook(info)));
+    let lines = LINES.lock().unwrap();
+    assert_eq!(lines[1], lazy_line);
+    assert_eq!(lines[3], force_mut_line);
+}
+
+#[test]
+fn option_lock_get_or_spin_init() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let lock = Arc::new(OptionLock::<Vec<usize>>::empty());
+    let inits = Arc::new(AtomicUsize::new(0));
+    let handles = (0..4)
+        .map(|index| {
+            let lock = lock.clone();
+            let inits = inits.clone();
+            std::thread::spawn(move || {
+                let mut guard = lock.get_or_spin_init(|| {
+                    inits.fetch_add(1, Ordering::Relaxed);
+                    std::thread::yield_now();
+                    Vec::new()
+                });
+                guard.push(index);
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(inits.load(Ordering::Relaxed), 1);
+    let mut values = lock.try_take().unwrap();
+    values.sort_unstable();
+    assert_eq!(values, [0, 1, 2, 3]);
+}
+
+#[test]
+fn option_lock_layout() {
+    use std::mem::size_of;
+    #[cfg(not(any(
+        all(feature = "debug_reentrancy", debug_assertions),
+        feature = "ticket",
+        feature = "track_hold_time",
+        feature = "instrument"
+    )))]
+    {
+        assert_eq!(size_of::<OptionLock<u8>>(), 2);
+        assert_eq!(size_of::<OptionLock<u32>>(), 8);
+        assert_eq!(
+            size_of::<OptionLock<String>>(),
+            size_of::<String>() + std::mem::align_of::<String>()
+        );
+    }
+    // the ticket counter adds four bytes, with at least four byte alignment
+    #[cfg(all(
+        feature = "ticket",
+        not(any(
+            all(feature = "debug_reentrancy", debug_assertions),
+            feature = "track_hold_time",
+            feature = "instrument"
+        ))
+    ))]
+    {
+        assert_eq!(size_of::<OptionLock<()>>(), 8);
+        assert_eq!(size_of::<OptionLock<u8>>(), 8);
+        assert_eq!(size_of::<OptionLock<u32>>(), 12);
+        assert_eq!(
+            size_of::<OptionLock<String>>(),
+            size_of::<String>() + std::mem::align_of::<String>()
+        );
+    }
+    assert_eq!(size_of::<Mutex<String>>(), size_of::<OptionLock<String>>());
+    assert_eq!(
+        size_of::<OnceCell<String>>(),
+        size_of::<OptionLock<String>>()
+    );
+}
+
+#[test]
+fn option_lock_try_take_all() {
+    let slots = [OptionLock::new(1), OptionLock::empty(), OptionLock::new(3)];
+    assert_eq!(
+        OptionLock::try_take_all(&slots),
+        Err(OptionLockError::FillState)
+    );
+    assert!(slots[0].is_some_unlocked() && slots[2].is_some_unlocked());
+    slots[1].try_fill(2).unwrap();
+    let guard = slots[2].try_lock().unwrap();
+    assert_eq!(
+        OptionLock::try_take_all(&slots),
+        Err(OptionLockError::Unavailable)
+    );
+    drop(guard);
+    assert!(slots.iter().all(OptionLock::is_some_unlocked));
+    assert_eq!(OptionLock::try_take_all(&slots), Ok(vec![1, 2, 3]));
+    assert!(slots.iter().all(OptionLock::is_none_unlocked));
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn wake_slot_set_poll() {
+    use std::future::poll_fn;
+    let slot = Arc::new(WakeSlot::new());
+    let setter = {
+        let slot = slot.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            slot.set(5);
+        })
+    };
+    assert_eq!(block_on(poll_fn(|cx| slot.poll(cx))), 5);
+    setter.join().unwrap();
+    slot.set(6);
+    slot.set(7);
+    assert_eq!(block_on(poll_fn(|cx| slot.poll(cx))), 7);
+}
+
+#[test]
+fn option_lock_try_clone_opt() {
+    let lock = OptionLock::<String>::empty();
+    assert_eq!(lock.try_clone(), Err(OptionLockError::FillState));
+    assert_eq!(lock.try_clone_opt(), Ok(None));
+    lock.try_fill("a".to_string()).unwrap();
+    assert_eq!(lock.try_clone_opt(), Ok(Some("a".to_string())));
+    let guard = lock.try_lock().unwrap();
+    assert_eq!(lock.try_clone_opt(), Err(OptionLockError::Unavailable));
+    drop(guard);
+}
+
+#[test]
+fn option_lock_set_dropping() {
+    let first = Arc::new(1);
+    let second = Arc::new(2);
+    let mut lock = OptionLock::empty();
+    lock.set_dropping(first.clone());
+    lock.set_dropping(second.clone());
+    assert_eq!(Arc::strong_count(&first), 1);
+    assert_eq!(lock.try_clone().as_deref(), Ok(&2));
+
+    let mut guard = lock.try_lock().unwrap();
+    guard.set(first.clone());
+    assert_eq!(Arc::strong_count(&second), 1);
+    guard.take();
+    guard.set(second.clone());
+    drop(guard);
+    assert_eq!(lock.try_take().as_deref(), Ok(&2));
+    assert_eq!(Arc::strong_count(&first), 1);
+}
+
+#[test]
+fn option_lock_try_reserve() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    let lock = OptionLock::<u32>::empty();
+    let reserved = lock.try_reserve().unwrap();
+    assert!(lock.is_locked());
+    assert_eq!(
+        lock.try_reserve().unwrap_err(),
+        OptionLockError::Unavailable
+    );
+    reserved.commit(1);
+    assert_eq!(lock.try_reserve().unwrap_err(), OptionLockError::FillState);
+    assert_eq!(lock.try_take(), Ok(1));
+
+    lock.try_reserve().unwrap().cancel();
+    assert!(lock.is_none_unlocked());
+
+    // dropping the guard releases the slot empty
+    drop(lock.try_reserve().unwrap());
+    assert!(lock.is_none_unlocked());
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut reserved = lock.try_reserve().unwrap();
+        reserved.set_strict(true);
+        drop(reserved);
+    }));
+    assert!(result.is_err());
+    assert!(lock.is_none_unlocked());
+}
+
+#[test]
+fn mutex_spin_lock_arc() {
+    let mutex = Arc::new(Mutex::new(0));
+    let guard = mutex.try_lock_arc().unwrap();
+    let waiter = {
+        let mutex = mutex.clone();
+        std::thread::spawn(move || {
+            let mut guard = mutex.spin_lock_arc().unwrap();
+            *guard += 1;
+        })
+    };
+    std::thread::yield_now();
+    drop(guard);
+    waiter.join().unwrap();
+    assert_eq!(mutex.try_copy(), Ok(1));
+    assert_eq!(Arc::strong_count(&mutex), 1);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn option_guard_drop_checks_state() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::Ordering;
+    let lock = OptionLock::new(1);
+    let guard = lock.try_lock().unwrap();
+    // deliberately release the lock while the guard is held
+    unsafe { lock.raw_state() }.store(state::AVAILABLE, Ordering::Release);
+    assert!(catch_unwind(AssertUnwindSafe(|| drop(guard))).is_err());
+    assert_eq!(lock.try_take(), Ok(1));
+}
+
+#[test]
+fn once_cell_slow_init_waiters() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let cell = Arc::new(OnceCell::<u32>::empty());
+    let inits = Arc::new(AtomicUsize::new(0));
+    let threads = (0..4)
+        .map(|_| {
+            let cell = cell.clone();
+            let inits = inits.clone();
+            std::thread::spawn(move || {
+                *cell.get_or_init(|| {
+                    inits.fetch_add(1, Ordering::Relaxed);
+                    std::thread::sleep(std::time::Duration::from_millis(50));
+                    5
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 5);
+    }
+    assert_eq!(inits.load(Ordering::Relaxed), 1);
+    // the record of the parked waiters is cleared when the lock is released
+    assert!(!cell.is_locked());
+    assert_eq!(Arc::try_unwrap(cell).unwrap().into_inner(), Some(5));
+}
+
+#[test]
+fn option_lock_visit() {
+    use std::ops::ControlFlow;
+    let lock = OptionLock::new(0u32);
+    let total = loop {
+        match lock.visit(|guard| {
+            let value = guard.as_mut().unwrap();
+            *value += 1;
+            if *value == 3 {
+                ControlFlow::Break(*value)
+            } else {
+                ControlFlow::Continue(())
+            }
+        }) {
+            Ok(ControlFlow::Break(total)) => break total,
+            Ok(ControlFlow::Continue(())) => assert!(!lock.is_locked()),
+            Err(err) => panic!("{:?}", err),
+        }
+    };
+    assert_eq!(total, 3);
+    assert!(!lock.is_locked());
+    let _guard = lock.try_lock().unwrap();
+    assert_eq!(
+        lock.visit(|_| ControlFlow::<()>::Continue(())),
+        Err(OptionLockError::Unavailable)
+    );
+}
+
+#[test]
+fn option_lock_try_take_opt() {
+    let lock = OptionLock::new(1);
+    assert_eq!(lock.try_take_opt(), Ok(Some(1)));
+    assert_eq!(lock.try_take_opt(), Ok(None));
+    assert_eq!(lock.try_take(), Err(OptionLockError::FillState));
+    let _guard = lock.try_lock().unwrap();
+    assert_eq!(lock.try_take_opt(), Err(OptionLockError::Unavailable));
+}
+
+#[cfg(feature = "track_hold_time")]
+#[test]
+fn option_lock_hold_time() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    static WARNINGS: AtomicUsize = AtomicUsize::new(0);
+    fn warn(held: Duration) {
+        assert!(held > Duration::from_millis(5));
+        WARNINGS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let lock = Arc::new(OptionLock::new(1));
+    assert_eq!(lock.last_hold_duration(), None);
+    lock.warn_if_held_longer_than(Duration::from_millis(5), warn);
+    let guard = lock.try_lock().unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    drop(guard);
+    assert!(lock.last_hold_duration().unwrap() >= Duration::from_millis(10));
+    assert_eq!(WARNINGS.load(Ordering::Relaxed), 1);
+
+    // the hold time of an owned guard is measured until it is dropped
+    let guard = lock.try_get_arc().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    drop(guard);
+    assert!(lock.last_hold_duration().unwrap() >= Duration::from_millis(20));
+    let _ = lock.try_take();
+    assert!(lock.last_hold_duration().unwrap() < Duration::from_millis(20));
+}
+
+#[test]
+fn lazy_lock_static() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static INITS: AtomicUsize = AtomicUsize::new(0);
+    static COUNTER: LazyLock<u32> = LazyLock::new(|| {
+        INITS.fetch_add(1, Ordering::Relaxed);
+        10
+    });
+    assert!(!LazyLock::is_initialized(&COUNTER));
+    let threads = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                let mut guard = COUNTER.spin_get();
+                *guard += 1;
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert!(LazyLock::is_initialized(&COUNTER));
+    assert_eq!(INITS.load(Ordering::Relaxed), 1);
+    assert_eq!(COUNTER.try_copy(), Ok(14));
+}
+
+#[cfg(feature = "instrument")]
+#[test]
+fn option_lock_observer() {
+    use option_lock::LockState::{self, *};
+    use std::sync::Mutex as StdMutex;
+
+    static EVENTS: StdMutex<Vec<(LockState, LockState)>> = StdMutex::new(Vec::new());
+    fn record(from: LockState, to: LockState) {
+        EVENTS.lock().unwrap().push((from, to));
+    }
+
+    let lock = OptionLock::<u32>::empty().with_observer(record);
+    lock.try_fill(1).unwrap();
+    assert_eq!(lock.try_take(), Ok(1));
+    let guard = lock.try_lock().unwrap();
+    assert!(lock.try_lock().is_err());
+    drop(guard);
+    assert_eq!(
+        *EVENTS.lock().unwrap(),
+        vec![
+            (Empty, LockedEmpty),
+            (LockedFilled, Filled),
+            (Filled, LockedFilled),
+            (LockedEmpty, Empty),
+            (Empty, LockedEmpty),
+            (LockedEmpty, Empty),
+        ]
+    );
+}